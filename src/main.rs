@@ -7,6 +7,7 @@ use runtime::runtime::Runtime;
 use std::env;
 use std::fs;
 use std::path::Path;
+use stdlib::debug::assertion_summary;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -18,6 +19,8 @@ fn main() {
     }
 
     let mut run_tests = false;
+    let mut trace = false;
+    let mut profile = false;
     let mut file_path: Option<&str> = None;
     for arg in args.iter().skip(1) {
         if arg == "--test" {
@@ -25,6 +28,16 @@ fn main() {
             continue;
         }
 
+        if arg == "--trace" {
+            trace = true;
+            continue;
+        }
+
+        if arg == "--profile" {
+            profile = true;
+            continue;
+        }
+
         if file_path.is_some() {
             eprintln!("Unexpected argument '{}'", arg);
             std::process::exit(1);
@@ -58,13 +71,26 @@ fn main() {
                 .unwrap_or_else(|| Path::new("."))
                 .to_path_buf();
             let mut runtime = Runtime::new_with_base_dir(base_dir);
+            runtime.set_trace(trace);
+            if profile {
+                runtime.enable_profiling();
+            }
             let result = if run_tests {
                 runtime.execute_tests(&statements)
             } else {
                 runtime.execute(&statements).map(|_| ())
             };
+            if let Some(summary) = assertion_summary() {
+                println!("{}", summary);
+            }
+            if profile {
+                eprintln!("Profile (function calls):");
+                for (name, count) in runtime.profile_report() {
+                    eprintln!("  {}: {}", name, count);
+                }
+            }
             if let Err(e) = result {
-                eprintln!("{}", e);
+                eprintln!("{}", e.report());
                 std::process::exit(1);
             }
         }
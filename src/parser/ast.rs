@@ -1,40 +1,70 @@
-use std::collections::HashMap;
+use crate::parser::symbol::Name;
+use indexmap::IndexMap;
+
+/// Object properties, kept in insertion order so key iteration is stable.
+pub(crate) type ObjectMap = IndexMap<String, Expression>;
+
+/// A node tagged with the 1-based source line it starts on.
+#[derive(Debug, Clone)]
+pub(crate) struct Spanned<T> {
+    pub(crate) line: usize,
+    pub(crate) node: T,
+}
 
 #[derive(Debug, Clone)]
 pub(crate) enum Statement {
     Set {
-        var: String,
+        var: Name,
         value: Expression,
     },
     Function {
-        name: String,
-        params: Vec<String>,
-        body: Vec<Statement>,
+        name: Name,
+        params: Vec<Name>,
+        body: Vec<Spanned<Statement>>,
         exported: bool,
     },
     Print {
         expr: Expression,
     },
+    Unset {
+        var: Name,
+    },
+    /// `set _ expr`: evaluates `expr` for its side effects without binding it.
+    Discard {
+        expr: Expression,
+    },
     Return {
         expr: Expression,
     },
     If {
         condition: Expression,
-        body: Vec<Statement>,
-        else_body: Option<Vec<Statement>>,
+        body: Vec<Spanned<Statement>>,
+        else_body: Option<Vec<Spanned<Statement>>>,
     },
     FunctionCall {
-        name: String,
+        name: Name,
         args: Vec<Expression>,
     },
     ForLoop {
-        variable: String,
+        variable: Name,
         iterable: Expression,
-        body: Vec<Statement>,
+        body: Vec<Spanned<Statement>>,
+    },
+    /// `for i from start to end [step n]`; `end` is inclusive.
+    RangeLoop {
+        variable: Name,
+        start: Expression,
+        end: Expression,
+        step: Option<Expression>,
+        body: Vec<Spanned<Statement>>,
     },
     WhileLoop {
         condition: Expression,
-        body: Vec<Statement>,
+        body: Vec<Spanned<Statement>>,
+    },
+    Repeat {
+        count_expr: Expression,
+        body: Vec<Spanned<Statement>>,
     },
     PropertySet {
         object: Expression,
@@ -45,19 +75,38 @@ pub(crate) enum Statement {
         module_name: String,
         file_path: String,
     },
+    ImportFunctions {
+        names: Vec<String>,
+        file_path: String,
+        exported: bool,
+    },
     Test {
         name: String,
-        body: Vec<Statement>,
+        body: Vec<Spanned<Statement>>,
+    },
+    Benchmark {
+        label: String,
+        body: Vec<Spanned<Statement>>,
     },
     Break,
     Continue,
     TryCatch {
-        try_body: Vec<Statement>,
-        error_var: String,
-        catch_body: Vec<Statement>,
+        try_body: Vec<Spanned<Statement>>,
+        error_var: Name,
+        catch_body: Vec<Spanned<Statement>>,
     },
 }
 
+/// Renders a float so it never reads as an integer: `3.0` prints as `3.0`, not
+/// `3`. Other values use the shortest form that round-trips.
+pub(crate) fn format_float(value: f64) -> String {
+    if value.is_finite() && value.fract() == 0.0 {
+        format!("{:.1}", value)
+    } else {
+        value.to_string()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum InterpolationPart {
     Text(String),
@@ -67,14 +116,15 @@ pub enum InterpolationPart {
 #[derive(Debug, Clone)]
 pub(crate) enum Expression {
     Number(i32),
+    Float(f64),
     Boolean(bool),
     StringLiteral(String),
     StringInterpolation {
         parts: Vec<InterpolationPart>,
     },
-    Variable(String),
+    Variable(Name),
     FunctionCall {
-        name: String,
+        name: Name,
         args: Vec<Expression>,
     },
     Comparison {
@@ -91,10 +141,20 @@ pub(crate) enum Expression {
         operator: String,
         expr: Box<Expression>,
     },
+    Conditional {
+        condition: Box<Expression>,
+        then_expr: Box<Expression>,
+        else_expr: Box<Expression>,
+    },
     Undefined,
     Null,
     Array(Vec<Expression>),
-    Object(HashMap<String, Expression>),
+    Object(ObjectMap),
+    /// Object returned by `object_freeze`. It reads like an object, but property
+    /// assignment on it fails.
+    FrozenObject(ObjectMap),
+    /// Dictionary with number or string keys, built by the `map_*` functions.
+    Map(Vec<(Expression, Expression)>),
     PropertyAccess {
         object: Box<Expression>,
         property: Box<Expression>,
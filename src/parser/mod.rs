@@ -2,3 +2,5 @@ pub mod ast;
 pub mod error;
 #[allow(clippy::module_inception)]
 pub mod parser;
+pub mod render;
+pub mod symbol;
@@ -1,5 +1,6 @@
-use crate::parser::ast::{Expression, InterpolationPart, Statement};
+use crate::parser::ast::{Expression, InterpolationPart, ObjectMap, Spanned, Statement};
 use crate::parser::error::ParseError;
+use crate::parser::symbol::intern;
 use std::collections::VecDeque;
 
 type ParseResult<T> = Result<T, ParseError>;
@@ -11,11 +12,11 @@ struct SourceLine {
 }
 
 #[cfg(test)]
-pub(crate) fn parse_program(code: &str) -> Vec<Statement> {
+pub(crate) fn parse_program(code: &str) -> Vec<Spanned<Statement>> {
     try_parse_program(code).unwrap_or_else(|err| panic!("{}", err))
 }
 
-pub(crate) fn try_parse_program(code: &str) -> ParseResult<Vec<Statement>> {
+pub(crate) fn try_parse_program(code: &str) -> ParseResult<Vec<Spanned<Statement>>> {
     let mut lines: VecDeque<SourceLine> = code
         .lines()
         .enumerate()
@@ -40,7 +41,10 @@ enum BlockTerminator {
     Catch(SourceLine),
 }
 
-fn parse_block(lines: &mut VecDeque<SourceLine>, expect_end: bool) -> ParseResult<Vec<Statement>> {
+fn parse_block(
+    lines: &mut VecDeque<SourceLine>,
+    expect_end: bool,
+) -> ParseResult<Vec<Spanned<Statement>>> {
     parse_block_with_terminators(lines, expect_end, false, false).map(|(body, _)| body)
 }
 
@@ -49,23 +53,30 @@ fn parse_block_with_terminators(
     expect_end: bool,
     allow_else: bool,
     allow_catch: bool,
-) -> ParseResult<(Vec<Statement>, Option<BlockTerminator>)> {
+) -> ParseResult<(Vec<Spanned<Statement>>, Option<BlockTerminator>)> {
     let mut statements = Vec::new();
 
     while let Some(line) = lines.pop_front() {
-        match line.text.split_whitespace().next() {
-            Some("set") => statements.push(parse_set_statement(&line, lines)?),
-            Some("function") | Some("export") => statements.push(parse_function(lines, &line)?),
-            Some("if") => statements.push(parse_if_statement(lines, &line)?),
-            Some("try") => statements.push(parse_try_catch_statement(lines, &line)?),
-            Some("print") => statements.push(parse_print_statement(&line)?),
-            Some("return") => statements.push(parse_return_statement(&line)?),
-            Some("break") => statements.push(parse_break_statement(&line)?),
-            Some("continue") => statements.push(parse_continue_statement(&line)?),
-            Some("for") => statements.push(parse_for_loop(lines, &line)?),
-            Some("while") => statements.push(parse_while_loop(lines, &line)?),
-            Some("import") => statements.push(parse_import_statement(&line)?),
-            Some("test") => statements.push(parse_test_block(lines, &line)?),
+        let statement = match line.text.split_whitespace().next() {
+            Some("set") => parse_set_statement(&line, lines)?,
+            Some("export") if line.text["export".len()..].trim_start().starts_with('{') => {
+                parse_reexport_statement(&line)?
+            }
+            Some("function") | Some("export") => parse_function(lines, &line)?,
+            Some("if") => parse_if_statement(lines, &line)?,
+            Some("try") => parse_try_catch_statement(lines, &line)?,
+            Some("print") => parse_print_statement(&line)?,
+            Some("return") => parse_return_statement(&line)?,
+            Some("break") => parse_break_statement(&line)?,
+            Some("continue") => parse_continue_statement(&line)?,
+            Some("unset") => parse_unset_statement(&line)?,
+            Some(keyword @ ("incr" | "decr")) => parse_step_statement(&line, keyword)?,
+            Some("for") => parse_for_loop(lines, &line)?,
+            Some("while") => parse_while_loop(lines, &line)?,
+            Some("repeat") => parse_repeat_loop(lines, &line)?,
+            Some("import") => parse_import_statement(&line)?,
+            Some("test") => parse_test_block(lines, &line)?,
+            Some("benchmark") => parse_benchmark_block(lines, &line)?,
             Some("else") => {
                 if allow_else {
                     return Ok((statements, Some(BlockTerminator::Else(line))));
@@ -94,17 +105,20 @@ fn parse_block_with_terminators(
 
                 let expr =
                     parse_expression(&line.text).map_err(|err| err.with_line(line.number))?;
-                if let Expression::FunctionCall { name, args } = expr {
-                    statements.push(Statement::FunctionCall { name, args });
-                } else {
+                let Expression::FunctionCall { name, args } = expr else {
                     return Err(ParseError::at_line(
                         line.number,
                         format!("Unknown statement: {}", line.text),
                     ));
-                }
+                };
+                Statement::FunctionCall { name, args }
             }
-            None => {}
-        }
+            None => continue,
+        };
+        statements.push(Spanned {
+            line: line.number,
+            node: statement,
+        });
     }
 
     if expect_end {
@@ -141,6 +155,13 @@ fn parse_set_statement(
             format!("Invalid set statement: {}", first_line.text),
         ));
     };
+    let (compound_operator, value_part) = split_compound_operator(value_part);
+    if value_part.is_empty() {
+        return Err(ParseError::at_line(
+            first_line.number,
+            format!("Invalid set statement: {}", first_line.text),
+        ));
+    }
     let mut value_str = value_part.to_string();
 
     if value_str == "{" {
@@ -198,9 +219,21 @@ fn parse_set_statement(
         value_str = result;
     }
 
-    let value = parse_expression(&value_str).map_err(|err| err.with_line(first_line.number))?;
+    let mut value = parse_expression(&value_str).map_err(|err| err.with_line(first_line.number))?;
+    let target_expr = parse_expression(target).map_err(|err| err.with_line(first_line.number))?;
 
-    match parse_expression(target).map_err(|err| err.with_line(first_line.number))? {
+    // `set x += v` is sugar for `set x x + v`; reading the target first makes an
+    // undefined variable an error rather than a fresh binding.
+    if let Some(operator) = compound_operator {
+        value = Expression::BinaryOperation {
+            left: Box::new(target_expr.clone()),
+            operator: operator.to_string(),
+            right: Box::new(value),
+        };
+    }
+
+    match target_expr {
+        Expression::Variable(var) if &*var == "_" => Ok(Statement::Discard { expr: value }),
         Expression::Variable(var) => Ok(Statement::Set { var, value }),
         Expression::PropertyAccess { object, property } => Ok(Statement::PropertySet {
             object: *object,
@@ -214,6 +247,18 @@ fn parse_set_statement(
     }
 }
 
+/// Splits a leading `+=`, `-=`, `*=` or `/=` off a set statement's value.
+fn split_compound_operator(value: &str) -> (Option<&'static str>, &str) {
+    for (prefix, operator) in [("+=", "+"), ("-=", "-"), ("*=", "*"), ("/=", "/")] {
+        // No expression starts with one of these, so the operand may follow
+        // without a space (`set x +=1`).
+        if let Some(rest) = value.strip_prefix(prefix) {
+            return (Some(operator), rest.trim());
+        }
+    }
+    (None, value)
+}
+
 fn parse_function(lines: &mut VecDeque<SourceLine>, header: &SourceLine) -> ParseResult<Statement> {
     let exported = header.text.starts_with("export");
     let header_text = header.text.trim_start_matches("export ");
@@ -231,13 +276,13 @@ fn parse_function(lines: &mut VecDeque<SourceLine>, header: &SourceLine) -> Pars
         .split(',')
         .map(str::trim)
         .filter(|param| !param.is_empty())
-        .map(String::from)
+        .map(intern)
         .collect();
 
     let body = parse_block(lines, true)?;
 
     Ok(Statement::Function {
-        name,
+        name: intern(&name),
         params,
         body,
         exported,
@@ -304,6 +349,40 @@ fn parse_return_statement(line: &SourceLine) -> ParseResult<Statement> {
     }
 }
 
+/// Parses `incr x` / `decr x` into `set x x + 1` / `set x x - 1`.
+fn parse_step_statement(line: &SourceLine, keyword: &str) -> ParseResult<Statement> {
+    let target = line.text.trim()[keyword.len()..].trim();
+    let var = match parse_expression(target) {
+        Ok(Expression::Variable(var)) => var,
+        _ => {
+            return Err(ParseError::at_line(
+                line.number,
+                format!("{} requires a variable name: {}", keyword, line.text),
+            ))
+        }
+    };
+
+    Ok(Statement::Set {
+        value: Expression::BinaryOperation {
+            left: Box::new(Expression::Variable(var.clone())),
+            operator: if keyword == "incr" { "+" } else { "-" }.to_string(),
+            right: Box::new(Expression::Number(1)),
+        },
+        var,
+    })
+}
+
+fn parse_unset_statement(line: &SourceLine) -> ParseResult<Statement> {
+    let target = line.text.trim()["unset".len()..].trim();
+    match parse_expression(target) {
+        Ok(Expression::Variable(var)) => Ok(Statement::Unset { var }),
+        _ => Err(ParseError::at_line(
+            line.number,
+            format!("unset requires a variable name: {}", line.text),
+        )),
+    }
+}
+
 fn parse_break_statement(line: &SourceLine) -> ParseResult<Statement> {
     if line.text.trim() != "break" {
         return Err(ParseError::at_line(
@@ -325,6 +404,16 @@ fn parse_continue_statement(line: &SourceLine) -> ParseResult<Statement> {
 }
 
 fn parse_import_statement(line: &SourceLine) -> ParseResult<Statement> {
+    let remainder = line.text["import".len()..].trim_start();
+    if remainder.starts_with('{') {
+        let (names, file_path) = parse_function_list_import(remainder, line.number)?;
+        return Ok(Statement::ImportFunctions {
+            names,
+            file_path,
+            exported: false,
+        });
+    }
+
     let parts: Vec<&str> = line.text.split_whitespace().collect();
     if parts.len() < 4 || parts[2] != "from" {
         return Err(ParseError::at_line(
@@ -354,10 +443,71 @@ fn parse_import_statement(line: &SourceLine) -> ParseResult<Statement> {
     })
 }
 
+fn parse_reexport_statement(line: &SourceLine) -> ParseResult<Statement> {
+    let remainder = line.text["export".len()..].trim_start();
+    let (names, file_path) = parse_function_list_import(remainder, line.number)?;
+    Ok(Statement::ImportFunctions {
+        names,
+        file_path,
+        exported: true,
+    })
+}
+
+fn parse_function_list_import(
+    remainder: &str,
+    line_number: usize,
+) -> ParseResult<(Vec<String>, String)> {
+    let invalid_syntax = || {
+        ParseError::at_line(
+            line_number,
+            "Invalid import syntax. Expected: import { name, ... } from 'file_path'",
+        )
+    };
+
+    let close = remainder.find('}').ok_or_else(invalid_syntax)?;
+    let names: Vec<String> = remainder[1..close]
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect();
+    if names.is_empty() {
+        return Err(invalid_syntax());
+    }
+    if let Some(name) = names
+        .iter()
+        .find(|name| consume_identifier(name) != name.len())
+    {
+        return Err(ParseError::at_line(
+            line_number,
+            format!("Invalid function name in import list: {}", name),
+        ));
+    }
+
+    let file_path_with_quotes = remainder[close + 1..]
+        .trim()
+        .strip_prefix("from")
+        .map(str::trim)
+        .ok_or_else(invalid_syntax)?;
+    let file_path = if file_path_with_quotes.len() >= 2
+        && ((file_path_with_quotes.starts_with('\'') && file_path_with_quotes.ends_with('\''))
+            || (file_path_with_quotes.starts_with('"') && file_path_with_quotes.ends_with('"')))
+    {
+        file_path_with_quotes[1..file_path_with_quotes.len() - 1].to_string()
+    } else {
+        return Err(ParseError::at_line(
+            line_number,
+            "File path must be quoted in import statement",
+        ));
+    };
+
+    Ok((names, file_path))
+}
+
 fn parse_else_branch(
     lines: &mut VecDeque<SourceLine>,
     line: &SourceLine,
-) -> ParseResult<Vec<Statement>> {
+) -> ParseResult<Vec<Spanned<Statement>>> {
     if line.text.trim() == "else start" {
         return parse_block(lines, true);
     }
@@ -380,7 +530,10 @@ fn parse_else_branch(
         number: line.number,
         text: else_if_header.to_string(),
     };
-    Ok(vec![parse_if_statement(lines, &synthetic_line)?])
+    Ok(vec![Spanned {
+        line: line.number,
+        node: parse_if_statement(lines, &synthetic_line)?,
+    }])
 }
 
 fn parse_try_catch_statement(
@@ -412,30 +565,43 @@ fn parse_try_catch_statement(
         .strip_prefix("catch")
         .map(str::trim)
         .filter(|value| !value.is_empty())
-        .unwrap_or("error")
-        .to_string();
+        .unwrap_or("error");
     let catch_body = parse_block(lines, true)?;
 
     Ok(Statement::TryCatch {
         try_body,
-        error_var,
+        error_var: intern(error_var),
         catch_body,
     })
 }
 
 fn parse_expression(expr: &str) -> ParseResult<Expression> {
-    parse_comparison(expr.trim())
+    parse_conditional(expr.trim())
+}
+
+fn parse_conditional(expr: &str) -> ParseResult<Expression> {
+    if let Some((question_pos, colon_pos)) = find_conditional_delimiters(expr) {
+        return Ok(Expression::Conditional {
+            condition: Box::new(parse_comparison(expr[..question_pos].trim())?),
+            then_expr: Box::new(parse_expression(&expr[question_pos + 1..colon_pos])?),
+            else_expr: Box::new(parse_expression(&expr[colon_pos + 1..])?),
+        });
+    }
+
+    parse_comparison(expr)
 }
 
 fn parse_comparison(expr: &str) -> ParseResult<Expression> {
+    // `in` is a keyword, so it is matched with its surrounding spaces to avoid
+    // splitting identifiers such as `index`.
     if let Some((operator_pos, operator)) =
-        find_top_level_binary_operator(expr, &["==", "!=", "<=", ">=", "<", ">"])
+        find_top_level_binary_operator(expr, &["==", "!=", "<=", ">=", "<", ">", " in "])
     {
         let left = parse_bitwise_or(expr[..operator_pos].trim())?;
         let right = parse_bitwise_or(expr[operator_pos + operator.len()..].trim())?;
         return Ok(Expression::Comparison {
             left: Box::new(left),
-            operator: operator.to_string(),
+            operator: operator.trim().to_string(),
             right: Box::new(right),
         });
     }
@@ -541,7 +707,7 @@ fn parse_primary(expr: &str) -> ParseResult<Expression> {
 
 fn parse_object(expr: &str) -> ParseResult<Expression> {
     let content = extract_between(expr, "{", "}");
-    let mut properties = std::collections::HashMap::new();
+    let mut properties = ObjectMap::new();
 
     let parts = split_top_level(content, ',');
 
@@ -570,6 +736,9 @@ fn parse_object(expr: &str) -> ParseResult<Expression> {
 fn parse_for_loop(lines: &mut VecDeque<SourceLine>, header: &SourceLine) -> ParseResult<Statement> {
     let header_text = strip_required_start_suffix(&header.text, "for", header.number)?;
     let parts: Vec<&str> = header_text.split_whitespace().collect();
+    if parts.len() >= 4 && parts[0] == "for" && parts[2] == "from" {
+        return parse_range_loop(lines, header, header_text);
+    }
     if parts.len() < 4 || parts[0] != "for" || parts[2] != "in" {
         return Err(ParseError::at_line(
             header.number,
@@ -577,7 +746,7 @@ fn parse_for_loop(lines: &mut VecDeque<SourceLine>, header: &SourceLine) -> Pars
         ));
     }
 
-    let variable = parts[1].to_string();
+    let variable = intern(parts[1]);
     let bind = parts[3..].join(" ");
     let iterable = parse_expression(&bind).map_err(|err| err.with_line(header.number))?;
     let body = parse_block(lines, true)?;
@@ -589,6 +758,50 @@ fn parse_for_loop(lines: &mut VecDeque<SourceLine>, header: &SourceLine) -> Pars
     })
 }
 
+fn parse_range_loop(
+    lines: &mut VecDeque<SourceLine>,
+    header: &SourceLine,
+    header_text: &str,
+) -> ParseResult<Statement> {
+    let invalid = || {
+        ParseError::at_line(
+            header.number,
+            format!("Invalid for range syntax: {}", header_text),
+        )
+    };
+
+    let mut words = header_text.splitn(4, char::is_whitespace);
+    let variable = intern(words.nth(1).ok_or_else(invalid)?);
+    let range = words.nth(1).ok_or_else(invalid)?;
+
+    let (to_pos, to) = find_top_level_binary_operator(range, &[" to "]).ok_or_else(invalid)?;
+    let start_str = range[..to_pos].trim();
+    let rest = &range[to_pos + to.len()..];
+    let (end_str, step_str) = match find_top_level_binary_operator(rest, &[" step "]) {
+        Some((step_pos, step)) => (&rest[..step_pos], Some(&rest[step_pos + step.len()..])),
+        None => (rest, None),
+    };
+
+    let parse = |text: &str| {
+        if text.trim().is_empty() {
+            return Err(invalid());
+        }
+        parse_expression(text).map_err(|err| err.with_line(header.number))
+    };
+    let start = parse(start_str)?;
+    let end = parse(end_str)?;
+    let step = step_str.map(parse).transpose()?;
+    let body = parse_block(lines, true)?;
+
+    Ok(Statement::RangeLoop {
+        variable,
+        start,
+        end,
+        step,
+        body,
+    })
+}
+
 fn parse_while_loop(
     lines: &mut VecDeque<SourceLine>,
     header: &SourceLine,
@@ -610,6 +823,27 @@ fn parse_while_loop(
     Ok(Statement::WhileLoop { condition, body })
 }
 
+fn parse_repeat_loop(
+    lines: &mut VecDeque<SourceLine>,
+    header: &SourceLine,
+) -> ParseResult<Statement> {
+    let header_text = strip_required_start_suffix(&header.text, "repeat", header.number)?;
+    let count_str = header_text
+        .strip_prefix("repeat")
+        .map(str::trim)
+        .unwrap_or("");
+    if count_str.is_empty() {
+        return Err(ParseError::at_line(
+            header.number,
+            "repeat count is required",
+        ));
+    }
+    let count_expr = parse_expression(count_str).map_err(|err| err.with_line(header.number))?;
+    let body = parse_block(lines, true)?;
+
+    Ok(Statement::Repeat { count_expr, body })
+}
+
 fn parse_array(expr: &str) -> ParseResult<Expression> {
     let content = extract_between(expr, "[", "]");
     let elements: Vec<Expression> = split_top_level(content, ',')
@@ -629,7 +863,7 @@ fn parse_array(expr: &str) -> ParseResult<Expression> {
 }
 
 fn parse_function_call(expr: &str) -> ParseResult<Expression> {
-    let name = extract_before(expr, "(").trim().to_string();
+    let name = intern(extract_before(expr, "(").trim());
     let args_str = extract_between(expr, "(", ")");
     let args = split_top_level(args_str, ',')
         .into_iter()
@@ -724,7 +958,19 @@ fn parse_base_expression(expr: &str) -> ParseResult<(Expression, usize)> {
             Ok((parse_expression(&expr[1..end - 1])?, end))
         }
         _ if first.is_ascii_digit() => {
-            let length = consume_digits(expr);
+            let mut length = consume_digits(expr);
+            let fraction_length = expr[length..]
+                .strip_prefix('.')
+                .map(consume_digits)
+                .unwrap_or(0);
+            if fraction_length > 0 {
+                length += 1 + fraction_length;
+                let number = expr[..length]
+                    .parse::<f64>()
+                    .map_err(|_| ParseError::new(format!("Invalid number literal: {}", expr)))?;
+                return Ok((Expression::Float(number), length));
+            }
+
             let number = expr[..length]
                 .parse::<i32>()
                 .map_err(|_| ParseError::new(format!("Invalid number literal: {}", expr)))?;
@@ -749,7 +995,7 @@ fn parse_base_expression(expr: &str) -> ParseResult<(Expression, usize)> {
                 "true" => Expression::Boolean(true),
                 "false" => Expression::Boolean(false),
                 "null" => Expression::Null,
-                _ => Expression::Variable(identifier.to_string()),
+                _ => Expression::Variable(intern(identifier)),
             };
             Ok((expression, first_identifier_len))
         }
@@ -761,20 +1007,37 @@ fn parse_test_block(
     header: &SourceLine,
 ) -> ParseResult<Statement> {
     let header_text = strip_required_start_suffix(&header.text, "test", header.number)?;
+    let name = quoted_block_label(header_text).unwrap_or_else(|| "Unnamed Test".to_string());
+
+    let body = parse_block(lines, true)?;
+
+    Ok(Statement::Test { name, body })
+}
+
+fn parse_benchmark_block(
+    lines: &mut VecDeque<SourceLine>,
+    header: &SourceLine,
+) -> ParseResult<Statement> {
+    let header_text = strip_required_start_suffix(&header.text, "benchmark", header.number)?;
+    let label = quoted_block_label(header_text).unwrap_or_else(|| "benchmark".to_string());
+
+    let body = parse_block(lines, true)?;
+
+    Ok(Statement::Benchmark { label, body })
+}
+
+/// Extracts the quoted label from a block header such as `test "name" start`.
+fn quoted_block_label(header_text: &str) -> Option<String> {
     let name_start = header_text.find('"').unwrap_or(0);
     let name_end = header_text[name_start + 1..]
         .find('"')
         .map(|idx| idx + name_start + 1)
         .unwrap_or(name_start);
-    let name = if name_start > 0 && name_end > name_start {
-        header_text[name_start + 1..name_end].to_string()
+    if name_start > 0 && name_end > name_start {
+        Some(header_text[name_start + 1..name_end].to_string())
     } else {
-        "Unnamed Test".to_string()
-    };
-
-    let body = parse_block(lines, true)?;
-
-    Ok(Statement::Test { name, body })
+        None
+    }
 }
 
 fn split_set_target_and_value(line: &str) -> Option<(&str, &str)> {
@@ -1160,6 +1423,51 @@ fn find_top_level_binary_operator<'a>(
     last_match
 }
 
+/// Finds the top-level `?` of a conditional expression and its matching `:`.
+/// Conditionals nested in the `then` branch are skipped so that
+/// `a ? b ? c : d : e` pairs the outer `?` with the last `:`.
+fn find_conditional_delimiters(expr: &str) -> Option<(usize, usize)> {
+    let mut depth_curly = 0;
+    let mut depth_square = 0;
+    let mut depth_paren = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut question_pos = None;
+    let mut nested = 0;
+
+    for (idx, ch) in expr.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+
+        match ch {
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' if !in_string => depth_curly += 1,
+            '}' if !in_string => depth_curly -= 1,
+            '[' if !in_string => depth_square += 1,
+            ']' if !in_string => depth_square -= 1,
+            '(' if !in_string => depth_paren += 1,
+            ')' if !in_string => depth_paren -= 1,
+            '?' | ':'
+                if !in_string && depth_curly == 0 && depth_square == 0 && depth_paren == 0 =>
+            {
+                match (ch, question_pos) {
+                    ('?', None) => question_pos = Some(idx),
+                    ('?', Some(_)) => nested += 1,
+                    (':', Some(_)) if nested > 0 => nested -= 1,
+                    (':', Some(question_pos)) => return Some((question_pos, idx)),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
 fn is_shift_fragment(expr: &str, idx: usize, operator: &str) -> bool {
     matches!(operator, "<" | ">")
         && (expr[idx..].starts_with(&format!("{operator}{operator}"))
@@ -1299,12 +1607,12 @@ mod tests {
         let statements = parse_program("set s \"hello # world\"\nprint s\n");
         assert_eq!(statements.len(), 2);
 
-        match &statements[0] {
+        match &statements[0].node {
             Statement::Set {
                 var,
                 value: Expression::StringLiteral(value),
             } => {
-                assert_eq!(var, "s");
+                assert_eq!(&**var, "s");
                 assert_eq!(value, "hello # world");
             }
             _ => panic!("Expected set statement with string literal"),
@@ -1316,12 +1624,12 @@ mod tests {
         let statements = parse_program("set x type_of(1 > 0)\n");
         assert_eq!(statements.len(), 1);
 
-        match &statements[0] {
+        match &statements[0].node {
             Statement::Set { var, value } => {
-                assert_eq!(var, "x");
+                assert_eq!(&**var, "x");
                 match value {
                     Expression::FunctionCall { name, args } => {
-                        assert_eq!(name, "type_of");
+                        assert_eq!(&**name, "type_of");
                         assert_eq!(args.len(), 1);
                         assert!(matches!(args[0], Expression::Comparison { .. }));
                     }
@@ -1382,11 +1690,11 @@ mod tests {
     fn parse_program_recognizes_null_literal() {
         let statements = parse_program("set value null\n");
         assert!(matches!(
-            &statements[0],
+            &statements[0].node,
             Statement::Set {
                 var,
                 value: Expression::Null
-            } if var == "value"
+            } if &**var == "value"
         ));
     }
 
@@ -1394,8 +1702,8 @@ mod tests {
     fn parse_program_handles_functions_without_parameters() {
         let statements = parse_program("function outer() start\nprint \"ok\"\nend\n");
         assert!(matches!(
-            &statements[0],
-            Statement::Function { name, params, .. } if name == "outer" && params.is_empty()
+            &statements[0].node,
+            Statement::Function { name, params, .. } if &**name == "outer" && params.is_empty()
         ));
     }
 
@@ -1405,7 +1713,7 @@ mod tests {
             "if true start\nprint 1\nelse if false start\nprint 2\nelse start\nprint 3\nend\n",
         );
         assert!(matches!(
-            &statements[0],
+            &statements[0].node,
             Statement::If {
                 else_body: Some(_),
                 ..
@@ -1413,17 +1721,30 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn parse_program_records_source_lines_of_statements() {
+        let statements =
+            parse_program("# header\n\nset x 1\nwhile x < 3 start\n    # bump\n    incr x\nend\n");
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].line, 3);
+        assert_eq!(statements[1].line, 4);
+        let Statement::WhileLoop { body, .. } = &statements[1].node else {
+            panic!("expected while loop");
+        };
+        assert_eq!(body[0].line, 6);
+    }
+
     #[test]
     fn parse_program_handles_try_catch() {
         let statements =
             parse_program("try start\nprint missing\ncatch err start\nprint err\nend\n");
         assert!(matches!(
-            &statements[0],
+            &statements[0].node,
             Statement::TryCatch {
                 error_var,
                 catch_body,
                 ..
-            } if error_var == "err" && !catch_body.is_empty()
+            } if &**error_var == "err" && !catch_body.is_empty()
         ));
     }
 
@@ -1432,14 +1753,14 @@ mod tests {
         let statements = parse_program("set value user.profile[key]\n");
         assert_eq!(statements.len(), 1);
 
-        match &statements[0] {
+        match &statements[0].node {
             Statement::Set { var, value } => {
-                assert_eq!(var, "value");
+                assert_eq!(&**var, "value");
                 match value {
                     Expression::PropertyAccess { object, property } => {
                         assert!(matches!(
                             property.as_ref(),
-                            Expression::Variable(name) if name == "key"
+                            Expression::Variable(name) if &**name == "key"
                         ));
                         match object.as_ref() {
                             Expression::PropertyAccess { object, property } => {
@@ -1449,7 +1770,7 @@ mod tests {
                                 ));
                                 assert!(matches!(
                                     object.as_ref(),
-                                    Expression::Variable(name) if name == "user"
+                                    Expression::Variable(name) if &**name == "user"
                                 ));
                             }
                             _ => panic!("Expected nested property access"),
@@ -1467,7 +1788,7 @@ mod tests {
         let statements = parse_program("set user[\"profile\"][key] \"gold\"\n");
         assert_eq!(statements.len(), 1);
 
-        match &statements[0] {
+        match &statements[0].node {
             Statement::PropertySet {
                 object,
                 property,
@@ -1475,7 +1796,7 @@ mod tests {
             } => {
                 assert!(matches!(
                     property,
-                    Expression::Variable(name) if name == "key"
+                    Expression::Variable(name) if &**name == "key"
                 ));
                 assert!(matches!(
                     value,
@@ -1489,7 +1810,7 @@ mod tests {
                         ));
                         assert!(matches!(
                             object.as_ref(),
-                            Expression::Variable(name) if name == "user"
+                            Expression::Variable(name) if &**name == "user"
                         ));
                     }
                     _ => panic!("Expected property path"),
@@ -1499,11 +1820,77 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_program_handles_selective_imports_and_reexports() {
+        let statements =
+            parse_program("import { inc, dec } from \"./m.vx\"\nexport { inc } from './m.vx'\n");
+        assert!(matches!(
+            &statements[0].node,
+            Statement::ImportFunctions { names, file_path, exported: false }
+                if names == &["inc".to_string(), "dec".to_string()] && file_path == "./m.vx"
+        ));
+        assert!(matches!(
+            &statements[1].node,
+            Statement::ImportFunctions { names, exported: true, .. } if names.len() == 1
+        ));
+    }
+
+    #[test]
+    fn parse_program_distinguishes_float_and_integer_literals() {
+        let statements = parse_program("set a 2.5\nset b 7\n");
+        assert!(matches!(
+            &statements[0].node,
+            Statement::Set {
+                value: Expression::Float(value),
+                ..
+            } if *value == 2.5
+        ));
+        assert!(matches!(
+            &statements[1].node,
+            Statement::Set {
+                value: Expression::Number(7),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_program_treats_underscore_set_target_as_discard() {
+        let statements = parse_program("set _ log(\"info\", \"x\")\nset _x 1\n");
+        assert!(matches!(
+            &statements[0].node,
+            Statement::Discard {
+                expr: Expression::FunctionCall { .. }
+            }
+        ));
+        assert!(matches!(&statements[1].node, Statement::Set { var, .. } if &**var == "_x"));
+    }
+
+    #[test]
+    fn parse_expression_nests_conditionals_in_both_branches() {
+        let statements = parse_program("set value a ? b ? 1 : 2 : c ? 3 : 4\n");
+        let Statement::Set {
+            value:
+                Expression::Conditional {
+                    condition,
+                    then_expr,
+                    else_expr,
+                },
+            ..
+        } = &statements[0].node
+        else {
+            panic!("expected conditional expression");
+        };
+        assert!(matches!(&**condition, Expression::Variable(name) if &**name == "a"));
+        assert!(matches!(&**then_expr, Expression::Conditional { .. }));
+        assert!(matches!(&**else_expr, Expression::Conditional { .. }));
+    }
+
     #[test]
     fn parse_expression_respects_operator_precedence() {
         let statements = parse_program("set value 1 + 2 * 3\n");
         assert!(matches!(
-            &statements[0],
+            &statements[0].node,
             Statement::Set {
                 value:
                     Expression::BinaryOperation {
@@ -1519,4 +1906,30 @@ mod tests {
                 )
         ));
     }
+
+    #[test]
+    fn parse_program_shares_one_allocation_per_identifier() {
+        let statements = parse_program(
+            "set total 0\nfor item in items start\n    set total total + item\nend\n",
+        );
+        let Statement::Set { var: declared, .. } = &statements[0].node else {
+            panic!("expected set statement");
+        };
+        let Statement::ForLoop { body, .. } = &statements[1].node else {
+            panic!("expected for loop");
+        };
+        let Statement::Set {
+            var: assigned,
+            value: Expression::BinaryOperation { left, .. },
+        } = &body[0].node
+        else {
+            panic!("expected set statement with binary operation");
+        };
+        let Expression::Variable(read) = left.as_ref() else {
+            panic!("expected variable operand");
+        };
+
+        assert!(std::sync::Arc::ptr_eq(declared, assigned));
+        assert!(std::sync::Arc::ptr_eq(declared, read));
+    }
 }
@@ -0,0 +1,259 @@
+//! Renders parsed statements back to Vexel source, for diagnostics such as
+//! `--trace`. Block statements render as their header line (`if x > 1 start`);
+//! nested operations are parenthesized so the text parses to the same tree.
+
+use crate::parser::ast::{format_float, Expression, InterpolationPart, Statement};
+
+pub(crate) fn render_statement(statement: &Statement) -> String {
+    match statement {
+        Statement::Set { var, value } => format!("set {} {}", var, render_expression(value)),
+        Statement::PropertySet {
+            object,
+            property,
+            value,
+        } => format!(
+            "set {} {}",
+            render_property_access(object, property),
+            render_expression(value)
+        ),
+        Statement::Function {
+            name,
+            params,
+            exported,
+            ..
+        } => format!(
+            "{}function {}({}) start",
+            if *exported { "export " } else { "" },
+            name,
+            params
+                .iter()
+                .map(|param| param.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Statement::Print { expr } => format!("print {}", render_expression(expr)),
+        Statement::Unset { var } => format!("unset {}", var),
+        Statement::Discard { expr } => format!("set _ {}", render_expression(expr)),
+        Statement::Return { expr } => format!("return {}", render_expression(expr)),
+        Statement::If { condition, .. } => format!("if {} start", render_expression(condition)),
+        Statement::FunctionCall { name, args } => render_call(name, args),
+        Statement::ForLoop {
+            variable, iterable, ..
+        } => format!("for {} in {} start", variable, render_expression(iterable)),
+        Statement::RangeLoop {
+            variable,
+            start,
+            end,
+            step,
+            ..
+        } => {
+            let step = step
+                .as_ref()
+                .map(|step| format!(" step {}", render_expression(step)))
+                .unwrap_or_default();
+            format!(
+                "for {} from {} to {}{} start",
+                variable,
+                render_expression(start),
+                render_expression(end),
+                step
+            )
+        }
+        Statement::WhileLoop { condition, .. } => {
+            format!("while {} start", render_expression(condition))
+        }
+        Statement::Repeat { count_expr, .. } => {
+            format!("repeat {} start", render_expression(count_expr))
+        }
+        Statement::Import {
+            module_name,
+            file_path,
+        } => format!("import {} from \"{}\"", module_name, file_path),
+        Statement::ImportFunctions {
+            names,
+            file_path,
+            exported,
+        } => format!(
+            "{} {{ {} }} from \"{}\"",
+            if *exported { "export" } else { "import" },
+            names.join(", "),
+            file_path
+        ),
+        Statement::Test { name, .. } => format!("test \"{}\" start", name),
+        Statement::Benchmark { label, .. } => format!("benchmark \"{}\" start", label),
+        Statement::Break => "break".to_string(),
+        Statement::Continue => "continue".to_string(),
+        Statement::TryCatch { .. } => "try start".to_string(),
+    }
+}
+
+pub(crate) fn render_expression(expr: &Expression) -> String {
+    match expr {
+        Expression::Number(n) => n.to_string(),
+        Expression::Float(n) => format_float(*n),
+        Expression::Boolean(b) => b.to_string(),
+        Expression::StringLiteral(s) => format!("\"{}\"", s),
+        Expression::StringInterpolation { parts } => {
+            let mut rendered = String::from("\"");
+            for part in parts {
+                match part {
+                    InterpolationPart::Text(text) => rendered.push_str(text),
+                    InterpolationPart::Expression(expr) => {
+                        rendered.push_str("${");
+                        rendered.push_str(&render_expression(expr));
+                        rendered.push('}');
+                    }
+                }
+            }
+            rendered.push('"');
+            rendered
+        }
+        Expression::Variable(name) => name.to_string(),
+        Expression::FunctionCall { name, args } => render_call(name, args),
+        Expression::Comparison {
+            left,
+            operator,
+            right,
+        }
+        | Expression::BinaryOperation {
+            left,
+            operator,
+            right,
+        } => format!(
+            "{} {} {}",
+            render_operand(left),
+            operator,
+            render_operand(right)
+        ),
+        Expression::UnaryOperation { operator, expr } => {
+            format!("{}{}", operator, render_operand(expr))
+        }
+        Expression::Conditional {
+            condition,
+            then_expr,
+            else_expr,
+        } => format!(
+            "{} ? {} : {}",
+            match condition.as_ref() {
+                Expression::Conditional { .. } => render_operand(condition),
+                _ => render_expression(condition),
+            },
+            render_expression(then_expr),
+            render_expression(else_expr)
+        ),
+        Expression::Undefined => "undefined".to_string(),
+        Expression::Null => "null".to_string(),
+        Expression::Array(items) => format!("[{}]", render_list(items)),
+        Expression::Object(properties) | Expression::FrozenObject(properties) => {
+            let fields: Vec<String> = properties
+                .iter()
+                .map(|(key, value)| format!("{}: {}", key, render_expression(value)))
+                .collect();
+            format!("{{{}}}", fields.join(", "))
+        }
+        Expression::Map(entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| {
+                    format!("{}: {}", render_expression(key), render_expression(value))
+                })
+                .collect();
+            format!("map{{{}}}", entries.join(", "))
+        }
+        Expression::PropertyAccess { object, property } => render_property_access(object, property),
+    }
+}
+
+fn render_call(name: &str, args: &[Expression]) -> String {
+    format!("{}({})", name, render_list(args))
+}
+
+fn render_list(items: &[Expression]) -> String {
+    items
+        .iter()
+        .map(render_expression)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Operands that are operations themselves are wrapped in parentheses.
+fn render_operand(expr: &Expression) -> String {
+    match expr {
+        Expression::Comparison { .. }
+        | Expression::BinaryOperation { .. }
+        | Expression::UnaryOperation { .. }
+        | Expression::Conditional { .. } => format!("({})", render_expression(expr)),
+        _ => render_expression(expr),
+    }
+}
+
+fn render_property_access(object: &Expression, property: &Expression) -> String {
+    match property {
+        Expression::StringLiteral(key) if is_identifier(key) => {
+            format!("{}.{}", render_operand(object), key)
+        }
+        _ => format!(
+            "{}[{}]",
+            render_operand(object),
+            render_expression(property)
+        ),
+    }
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render_statement;
+    use crate::parser::parser::parse_program;
+
+    fn render_first(source: &str) -> String {
+        let statements = parse_program(source);
+        render_statement(&statements[0].node)
+    }
+
+    #[test]
+    fn render_statement_round_trips_simple_statements() {
+        for source in [
+            "set total (a + 1) * 2",
+            "set user.name \"vexel\"",
+            "set grid[i].key null",
+            "set grid[i][\"two words\"] null",
+            "print \"total: ${total}\"",
+            "set _ array_push(items, [1, 2.5], {a: true})",
+            "return n <= 1 ? n : fib(n - 1)",
+            "unset total",
+            "print_all(-x, ~mask)",
+            "import { a, b } from \"./lib.vx\"",
+            "import lib from \"./lib.vx\"",
+        ] {
+            assert_eq!(render_first(source), source);
+        }
+    }
+
+    #[test]
+    fn render_statement_renders_block_headers() {
+        assert_eq!(
+            render_first("for i from 0 to n step 2 start\nend"),
+            "for i from 0 to n step 2 start"
+        );
+        assert_eq!(
+            render_first("export function add(a, b) start\nreturn a + b\nend"),
+            "export function add(a, b) start"
+        );
+        assert_eq!(
+            render_first("if x in items start\nend"),
+            "if x in items start"
+        );
+        assert_eq!(
+            render_first("test \"math\" start\nend"),
+            "test \"math\" start"
+        );
+    }
+}
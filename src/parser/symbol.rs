@@ -0,0 +1,43 @@
+use rustc_hash::FxHashSet;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+/// Identifier shared by every occurrence of the same variable or function name.
+///
+/// Cloning a `Name` only bumps a reference count, so the runtime can copy names
+/// into scopes and function tables without allocating.
+pub(crate) type Name = Arc<str>;
+
+thread_local! {
+    static NAMES: RefCell<FxHashSet<Name>> = RefCell::new(FxHashSet::default());
+}
+
+/// Returns the shared handle for `name`, allocating it the first time it is seen.
+pub(crate) fn intern(name: &str) -> Name {
+    NAMES.with(|names| {
+        let mut names = names.borrow_mut();
+        if let Some(existing) = names.get(name) {
+            return existing.clone();
+        }
+        let interned: Name = Arc::from(name);
+        names.insert(interned.clone());
+        interned
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::intern;
+    use std::sync::Arc;
+
+    #[test]
+    fn interning_the_same_name_reuses_one_allocation() {
+        let first = intern("counter");
+        let second = intern(&String::from("counter"));
+        let other = intern("total");
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(!Arc::ptr_eq(&first, &other));
+        assert_eq!(&*second, "counter");
+    }
+}
@@ -0,0 +1,202 @@
+//! Builtins that need the runtime, e.g. to call back into user functions.
+//!
+//! Plain native functions only see their evaluated arguments. The functions here
+//! need runtime state, such as calling a user function by name or inspecting
+//! variables, so they are dispatched before the native table is consulted.
+
+use super::repl;
+use super::runtime::{Runtime, RuntimeError};
+use crate::parser::ast::{Expression, ObjectMap};
+
+impl Runtime {
+    /// Returns `None` when `name` is not a runtime-backed builtin.
+    pub(super) fn call_builtin(
+        &self,
+        name: &str,
+        args: &[Expression],
+    ) -> Option<Result<Expression, RuntimeError>> {
+        match name {
+            "array_count" => Some(self.array_count(args)),
+            "array_flat_map" => Some(self.array_flat_map(args)),
+            "array_group_by" => Some(self.array_group_by(args)),
+            "array_partition" => Some(self.array_partition(args)),
+            "breakpoint" => Some(self.breakpoint(args)),
+            "defined" => Some(self.defined(args)),
+            "dispatch" => Some(self.dispatch(args)),
+            "memoize" => Some(self.memoize(args)),
+            "parallel_map" => Some(self.parallel_map(args)),
+            "thread_spawn" => Some(self.thread_spawn(args)),
+            "thread_join" => Some(self.thread_join(args)),
+            "thread_join_all" => Some(self.thread_join_all(args)),
+            "thread_status" => Some(self.thread_status(args)),
+            _ => None,
+        }
+    }
+
+    fn breakpoint(&self, args: &[Expression]) -> Result<Expression, RuntimeError> {
+        if !args.is_empty() {
+            return Err(RuntimeError::new("breakpoint takes no arguments"));
+        }
+        repl::breakpoint(&mut self.shared_scope_runtime());
+        Ok(Expression::Null)
+    }
+
+    fn defined(&self, args: &[Expression]) -> Result<Expression, RuntimeError> {
+        match args {
+            [Expression::StringLiteral(name)] => {
+                Ok(Expression::Boolean(self.lookup_variable(name).is_some()))
+            }
+            _ => Err(RuntimeError::new("defined expects a variable name string")),
+        }
+    }
+
+    fn dispatch(&self, args: &[Expression]) -> Result<Expression, RuntimeError> {
+        let (table, key, call_args) = match args {
+            [Expression::Object(table) | Expression::FrozenObject(table), Expression::StringLiteral(key), Expression::Array(call_args)] => {
+                (table, key, call_args)
+            }
+            _ => {
+                return Err(RuntimeError::new(
+                    "dispatch expects an object, a key string and an argument array",
+                ))
+            }
+        };
+
+        match table.get(key) {
+            Some(Expression::StringLiteral(function)) => {
+                self.call_with_arguments(function, call_args.clone())
+            }
+            Some(_) => Err(RuntimeError::new(format!(
+                "dispatch entry '{}' must be a function name string",
+                key
+            ))),
+            None => Err(RuntimeError::new(format!(
+                "dispatch table has no entry '{}'",
+                key
+            ))),
+        }
+    }
+
+    fn memoize(&self, args: &[Expression]) -> Result<Expression, RuntimeError> {
+        match args {
+            [Expression::StringLiteral(name)] => {
+                self.memoize_function(name)?;
+                Ok(Expression::Null)
+            }
+            _ => Err(RuntimeError::new("memoize expects a function name string")),
+        }
+    }
+
+    fn array_flat_map(&self, args: &[Expression]) -> Result<Expression, RuntimeError> {
+        let (items, function) = match args {
+            [Expression::Array(items), Expression::StringLiteral(function)] => (items, function),
+            _ => {
+                return Err(RuntimeError::new(
+                    "array_flat_map expects an array and a function name",
+                ))
+            }
+        };
+
+        let mut result = Vec::new();
+        for item in items {
+            match self.call_with_arguments(function, vec![item.clone()])? {
+                Expression::Array(mapped) => result.extend(mapped),
+                _ => {
+                    return Err(RuntimeError::new(format!(
+                        "array_flat_map callback '{}' must return an array",
+                        function
+                    )))
+                }
+            }
+        }
+        Ok(Expression::Array(result))
+    }
+
+    fn array_group_by(&self, args: &[Expression]) -> Result<Expression, RuntimeError> {
+        let (items, function) = match args {
+            [Expression::Array(items), Expression::StringLiteral(function)] => (items, function),
+            _ => {
+                return Err(RuntimeError::new(
+                    "array_group_by expects an array and a function name",
+                ))
+            }
+        };
+
+        let mut groups = ObjectMap::new();
+        for item in items {
+            let key = match self.call_with_arguments(function, vec![item.clone()])? {
+                Expression::StringLiteral(key) => key,
+                _ => {
+                    return Err(RuntimeError::new(format!(
+                        "array_group_by callback '{}' must return a string",
+                        function
+                    )))
+                }
+            };
+            match groups
+                .entry(key)
+                .or_insert_with(|| Expression::Array(Vec::new()))
+            {
+                Expression::Array(bucket) => bucket.push(item.clone()),
+                _ => unreachable!("group buckets are always arrays"),
+            }
+        }
+        Ok(Expression::Object(groups))
+    }
+
+    fn array_count(&self, args: &[Expression]) -> Result<Expression, RuntimeError> {
+        let (items, predicate) = match args {
+            [Expression::Array(items), Expression::StringLiteral(predicate)] => (items, predicate),
+            _ => {
+                return Err(RuntimeError::new(
+                    "array_count expects an array and a predicate name",
+                ))
+            }
+        };
+
+        let mut count = 0;
+        for item in items {
+            match self.call_with_arguments(predicate, vec![item.clone()])? {
+                Expression::Boolean(true) => count += 1,
+                Expression::Boolean(false) => {}
+                _ => {
+                    return Err(RuntimeError::new(format!(
+                        "array_count predicate '{}' must return a boolean",
+                        predicate
+                    )))
+                }
+            }
+        }
+        Ok(Expression::Number(count))
+    }
+
+    fn array_partition(&self, args: &[Expression]) -> Result<Expression, RuntimeError> {
+        let (items, predicate) = match args {
+            [Expression::Array(items), Expression::StringLiteral(predicate)] => (items, predicate),
+            _ => {
+                return Err(RuntimeError::new(
+                    "array_partition expects an array and a predicate name",
+                ))
+            }
+        };
+
+        let mut matches = Vec::new();
+        let mut rest = Vec::new();
+        for item in items {
+            match self.call_with_arguments(predicate, vec![item.clone()])? {
+                Expression::Boolean(true) => matches.push(item.clone()),
+                Expression::Boolean(false) => rest.push(item.clone()),
+                _ => {
+                    return Err(RuntimeError::new(format!(
+                        "array_partition predicate '{}' must return a boolean",
+                        predicate
+                    )))
+                }
+            }
+        }
+        Ok(Expression::Array(vec![
+            Expression::Array(matches),
+            Expression::Array(rest),
+        ]))
+    }
+}
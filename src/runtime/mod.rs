@@ -1,3 +1,6 @@
+mod builtins;
+mod operators;
 pub mod repl;
 #[allow(clippy::module_inception)]
 pub mod runtime;
+mod worker;
@@ -0,0 +1,279 @@
+use super::runtime::RuntimeError;
+use crate::parser::ast::Expression;
+
+/// Widens either numeric representation so mixed integer/float operands can be
+/// combined.
+fn as_float(value: &Expression) -> Option<f64> {
+    match value {
+        Expression::Number(n) => Some(*n as f64),
+        Expression::Float(n) => Some(*n),
+        _ => None,
+    }
+}
+
+/// Equality used for membership tests; only scalar values compare equal.
+fn scalars_equal(left: &Expression, right: &Expression) -> bool {
+    if let (Some(l), Some(r)) = (as_float(left), as_float(right)) {
+        return l == r;
+    }
+
+    match (left, right) {
+        (Expression::Boolean(l), Expression::Boolean(r)) => l == r,
+        (Expression::StringLiteral(l), Expression::StringLiteral(r)) => l == r,
+        (Expression::Null, Expression::Null) => true,
+        _ => false,
+    }
+}
+
+/// Implements `value in collection`: element membership for arrays, key presence
+/// for objects and maps and substring containment for strings.
+fn contains(collection: &Expression, value: &Expression) -> Result<bool, RuntimeError> {
+    match (collection, value) {
+        (Expression::Array(items), value) => {
+            Ok(items.iter().any(|item| scalars_equal(item, value)))
+        }
+        (
+            Expression::Object(properties) | Expression::FrozenObject(properties),
+            Expression::StringLiteral(key),
+        ) => Ok(properties.contains_key(key)),
+        (Expression::Map(entries), key) => Ok(entries
+            .iter()
+            .any(|(existing, _)| scalars_equal(existing, key))),
+        (Expression::StringLiteral(haystack), Expression::StringLiteral(needle)) => {
+            Ok(haystack.contains(needle.as_str()))
+        }
+        (Expression::Object(_) | Expression::FrozenObject(_), _)
+        | (Expression::StringLiteral(_), _) => Err(RuntimeError::type_error(
+            "Operator 'in' requires a string on the left for objects and strings",
+        )),
+        _ => Err(RuntimeError::type_error(
+            "Operator 'in' requires an array, object, map, or string on the right",
+        )),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ComparisonOperator {
+    Equal,
+    NotEqual,
+    Greater,
+    Less,
+    GreaterOrEqual,
+    LessOrEqual,
+    In,
+}
+
+impl ComparisonOperator {
+    pub(crate) fn parse(symbol: &str) -> Result<Self, RuntimeError> {
+        match symbol {
+            "==" => Ok(Self::Equal),
+            "!=" => Ok(Self::NotEqual),
+            ">" => Ok(Self::Greater),
+            "<" => Ok(Self::Less),
+            ">=" => Ok(Self::GreaterOrEqual),
+            "<=" => Ok(Self::LessOrEqual),
+            "in" => Ok(Self::In),
+            _ => Err(RuntimeError::new(format!(
+                "Unsupported comparison operator '{}'",
+                symbol
+            ))),
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+            Self::Greater => ">",
+            Self::Less => "<",
+            Self::GreaterOrEqual => ">=",
+            Self::LessOrEqual => "<=",
+            Self::In => "in",
+        }
+    }
+
+    pub(crate) fn apply(
+        self,
+        left: Expression,
+        right: Expression,
+    ) -> Result<Expression, RuntimeError> {
+        if self == Self::In {
+            return contains(&right, &left).map(Expression::Boolean);
+        }
+
+        // Every i32 is exactly representable as f64, so comparing through f64
+        // gives the same answer for integers and handles mixed operands.
+        if let (Some(l), Some(r)) = (as_float(&left), as_float(&right)) {
+            return Ok(Expression::Boolean(match self {
+                Self::Equal => l == r,
+                Self::NotEqual => l != r,
+                Self::Greater => l > r,
+                Self::Less => l < r,
+                Self::GreaterOrEqual => l >= r,
+                Self::LessOrEqual => l <= r,
+                Self::In => unreachable!("membership is handled above"),
+            }));
+        }
+
+        match (left, right) {
+            (Expression::Boolean(l), Expression::Boolean(r)) => match self {
+                Self::Equal => Ok(Expression::Boolean(l == r)),
+                Self::NotEqual => Ok(Expression::Boolean(l != r)),
+                _ => Err(RuntimeError::type_error(format!(
+                    "Unsupported comparison operator '{}' for booleans",
+                    self.symbol()
+                ))),
+            },
+            (Expression::StringLiteral(l), Expression::StringLiteral(r)) => match self {
+                Self::Equal => Ok(Expression::Boolean(l == r)),
+                Self::NotEqual => Ok(Expression::Boolean(l != r)),
+                _ => Err(RuntimeError::type_error(format!(
+                    "Unsupported comparison operator '{}' for strings",
+                    self.symbol()
+                ))),
+            },
+            _ => Err(RuntimeError::type_error(
+                "Comparison operands must both be numbers, booleans, or strings",
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    BitAnd,
+    BitOr,
+    ShiftLeft,
+    ShiftRight,
+}
+
+impl BinaryOperator {
+    pub(crate) fn parse(symbol: &str) -> Result<Self, RuntimeError> {
+        match symbol {
+            "+" => Ok(Self::Add),
+            "-" => Ok(Self::Subtract),
+            "*" => Ok(Self::Multiply),
+            "/" => Ok(Self::Divide),
+            "%" => Ok(Self::Modulo),
+            "&" => Ok(Self::BitAnd),
+            "|" => Ok(Self::BitOr),
+            "<<" => Ok(Self::ShiftLeft),
+            ">>" => Ok(Self::ShiftRight),
+            _ => Err(RuntimeError::new(format!(
+                "Unsupported binary operator '{}'",
+                symbol
+            ))),
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::Subtract => "-",
+            Self::Multiply => "*",
+            Self::Divide => "/",
+            Self::Modulo => "%",
+            Self::BitAnd => "&",
+            Self::BitOr => "|",
+            Self::ShiftLeft => "<<",
+            Self::ShiftRight => ">>",
+        }
+    }
+
+    pub(crate) fn apply(
+        self,
+        left: Expression,
+        right: Expression,
+    ) -> Result<Expression, RuntimeError> {
+        let (l, r) = match (left, right) {
+            (Expression::Number(l), Expression::Number(r)) => (l, r),
+            (l, r) => {
+                return match (as_float(&l), as_float(&r)) {
+                    (Some(l), Some(r)) => self.apply_floats(l, r),
+                    _ => Err(RuntimeError::type_error(format!(
+                        "Binary operator '{}' requires numeric operands",
+                        self.symbol()
+                    ))),
+                };
+            }
+        };
+
+        match self {
+            Self::Add => Ok(Expression::Number(l + r)),
+            Self::Subtract => Ok(Expression::Number(l - r)),
+            Self::Multiply => Ok(Expression::Number(l * r)),
+            Self::Divide if r != 0 => Ok(Expression::Number(l / r)),
+            Self::Modulo if r != 0 => Ok(Expression::Number(l % r)),
+            Self::Divide | Self::Modulo => Err(RuntimeError::new(
+                "Division and modulo by zero are not allowed",
+            )),
+            Self::BitAnd => Ok(Expression::Number(l & r)),
+            Self::BitOr => Ok(Expression::Number(l | r)),
+            Self::ShiftLeft if r >= 0 => Ok(Expression::Number(l.wrapping_shl(r as u32))),
+            Self::ShiftRight if r >= 0 => Ok(Expression::Number(l.wrapping_shr(r as u32))),
+            Self::ShiftLeft | Self::ShiftRight => Err(RuntimeError::new(
+                "Shift count must be a non-negative number",
+            )),
+        }
+    }
+
+    fn apply_floats(self, l: f64, r: f64) -> Result<Expression, RuntimeError> {
+        match self {
+            Self::Add => Ok(Expression::Float(l + r)),
+            Self::Subtract => Ok(Expression::Float(l - r)),
+            Self::Multiply => Ok(Expression::Float(l * r)),
+            Self::Divide if r != 0.0 => Ok(Expression::Float(l / r)),
+            Self::Modulo if r != 0.0 => Ok(Expression::Float(l % r)),
+            Self::Divide | Self::Modulo => Err(RuntimeError::new(
+                "Division and modulo by zero are not allowed",
+            )),
+            Self::BitAnd | Self::BitOr | Self::ShiftLeft | Self::ShiftRight => {
+                Err(RuntimeError::type_error(format!(
+                    "Binary operator '{}' requires integer operands",
+                    self.symbol()
+                )))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UnaryOperator {
+    Negate,
+    BitNot,
+}
+
+impl UnaryOperator {
+    pub(crate) fn parse(symbol: &str) -> Result<Self, RuntimeError> {
+        match symbol {
+            "-" => Ok(Self::Negate),
+            "~" => Ok(Self::BitNot),
+            _ => Err(RuntimeError::new(format!(
+                "Unsupported unary operator '{}'",
+                symbol
+            ))),
+        }
+    }
+
+    pub(crate) fn apply(self, value: Expression) -> Result<Expression, RuntimeError> {
+        match (self, value) {
+            (Self::Negate, Expression::Number(number)) => Ok(Expression::Number(-number)),
+            (Self::Negate, Expression::Float(number)) => Ok(Expression::Float(-number)),
+            (Self::BitNot, Expression::Number(number)) => Ok(Expression::Number(!number)),
+            (Self::BitNot, Expression::Float(_)) => Err(RuntimeError::type_error(
+                "Unary operator '~' requires an integer operand",
+            )),
+            (Self::Negate, _) => Err(RuntimeError::type_error(
+                "Unary operator '-' requires a numeric operand",
+            )),
+            (Self::BitNot, _) => Err(RuntimeError::type_error(
+                "Unary operator '~' requires a numeric operand",
+            )),
+        }
+    }
+}
@@ -4,60 +4,146 @@ use std::io::{self, Write};
 
 pub(crate) fn repl() {
     let mut runtime = Runtime::new();
-    let mut buffer = String::new();
-    let mut block_depth = 0usize;
+    let mut reader = BlockReader::default();
+    let mut pasted: Option<String> = None;
 
-    loop {
-        if block_depth > 0 {
-            print!("... ");
-        } else {
-            print!(">> ");
+    while let Some(input) = reader.read_line(if pasted.is_some() { "... " } else { ">> " }) {
+        if let Some(buffer) = &mut pasted {
+            if input.trim() == ".end" {
+                run_buffer(&mut runtime, buffer);
+                pasted = None;
+            } else {
+                buffer.push_str(&input);
+                buffer.push('\n');
+            }
+            continue;
         }
-        io::stdout().flush().unwrap();
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
-        let input = input.trim_end();
+        if input == "exit" {
+            break;
+        }
 
-        if input.is_empty() {
-            continue;
+        match input.trim() {
+            ".clear" => {
+                print!("\x1B[2J\x1B[1;1H");
+                continue;
+            }
+            ".reset" => {
+                runtime = Runtime::new();
+                reader = BlockReader::default();
+                continue;
+            }
+            ".paste" => {
+                pasted = Some(String::new());
+                continue;
+            }
+            _ => {}
         }
 
-        if input == "exit" {
+        if let Some(source) = reader.push(&input) {
+            run_buffer(&mut runtime, &source);
+        }
+    }
+}
+
+/// Pauses the program with a prompt that runs input against the paused scope.
+/// `continue` or the end of input resumes execution.
+pub(crate) fn breakpoint(runtime: &mut Runtime) {
+    println!("Breakpoint hit. Type 'continue' to resume.");
+    let mut reader = BlockReader::default();
+
+    loop {
+        let Some(input) = reader.read_line("(breakpoint) ") else {
+            println!();
             break;
+        };
+
+        if !reader.in_block() && input.trim() == "continue" {
+            break;
+        }
+
+        if let Some(source) = reader.push(&input) {
+            run_buffer(runtime, &source);
+        }
+    }
+}
+
+/// Reads prompt input line by line, buffering it until every block opened
+/// in it has been closed by its `end`.
+#[derive(Default)]
+struct BlockReader {
+    buffer: String,
+    block_depth: usize,
+}
+
+impl BlockReader {
+    fn in_block(&self) -> bool {
+        self.block_depth > 0
+    }
+
+    /// Prints `prompt`, or `... ` inside an open block, and reads the next
+    /// non-empty line. Returns `None` at the end of input.
+    fn read_line(&self, prompt: &str) -> Option<String> {
+        loop {
+            print!("{}", if self.in_block() { "... " } else { prompt });
+            io::stdout().flush().unwrap();
+
+            let mut input = String::new();
+            if io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+                return None;
+            }
+            let input = input.trim_end();
+            if !input.is_empty() {
+                return Some(input.to_string());
+            }
         }
+    }
 
-        if (input.starts_with("function ")
-            || input.starts_with("export function ")
-            || input.starts_with("if ")
-            || input.starts_with("try ")
-            || input.starts_with("for ")
-            || input.starts_with("while ")
-            || input.starts_with("test "))
-            && input.trim_end().ends_with(" start")
-        {
-            block_depth += 1;
+    /// Buffers `line` and returns the buffered source once no block is open.
+    fn push(&mut self, line: &str) -> Option<String> {
+        if opens_block(line) {
+            self.block_depth += 1;
         }
 
-        buffer.push_str(input);
-        buffer.push('\n');
+        self.buffer.push_str(line);
+        self.buffer.push('\n');
 
-        if input == "end" && block_depth > 0 {
-            block_depth -= 1;
+        if line.trim() == "end" && self.block_depth > 0 {
+            self.block_depth -= 1;
         }
 
-        if block_depth == 0 {
-            match try_parse_program(&buffer) {
-                Ok(statements) => {
-                    if let Err(e) = runtime.execute(&statements) {
-                        eprintln!("{}", e);
-                    }
-                }
-                Err(e) => {
-                    eprintln!("{}", e);
-                }
+        (self.block_depth == 0).then(|| std::mem::take(&mut self.buffer))
+    }
+}
+
+fn run_buffer(runtime: &mut Runtime, buffer: &str) {
+    match try_parse_program(buffer) {
+        Ok(statements) => {
+            if let Err(e) = runtime.execute(&statements) {
+                eprintln!("{}", e.report());
             }
-            buffer.clear();
+        }
+        Err(e) => {
+            eprintln!("{}", e);
         }
     }
 }
+
+/// Returns true for block headers whose `end` must be read before the buffered
+/// input can be parsed. `else` and `catch` continue an already open block.
+fn opens_block(line: &str) -> bool {
+    let line = line.trim();
+    const HEADERS: [&str; 9] = [
+        "function ",
+        "export function ",
+        "if ",
+        "try ",
+        "for ",
+        "while ",
+        "repeat ",
+        "test ",
+        "benchmark ",
+    ];
+
+    HEADERS.iter().any(|header| line.starts_with(header)) && line.ends_with(" start")
+}
@@ -1,5 +1,12 @@
-use crate::parser::ast::{Expression, InterpolationPart, Statement};
+use super::operators::{BinaryOperator, ComparisonOperator, UnaryOperator};
+use super::worker::{FunctionSnapshot, ModuleSnapshot, WorkerSnapshot};
+use crate::parser::ast::{
+    format_float, Expression, InterpolationPart, ObjectMap, Spanned, Statement,
+};
 use crate::parser::parser::try_parse_program;
+use crate::parser::render::render_statement;
+use crate::parser::symbol::{intern, Name};
+use crate::stdlib::debug::assertion_failures;
 use crate::stdlib::get_all_native_functions;
 use rustc_hash::FxHashMap as HashMap;
 use std::cell::RefCell;
@@ -7,9 +14,10 @@ use std::error::Error;
 use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
+use std::time::Instant;
 
-type VariableTable = HashMap<String, Expression>;
+type VariableTable = HashMap<Name, Expression>;
 
 #[derive(Clone)]
 struct VariableScope {
@@ -58,36 +66,129 @@ impl VariableScope {
 
 #[derive(Clone)]
 struct FunctionDefinition {
-    params: Vec<String>,
-    body: Vec<Statement>,
+    params: Vec<Name>,
+    body: Vec<Spanned<Statement>>,
     exported: bool,
     scope: Rc<VariableScope>,
-    base_dir: PathBuf,
+    base_dir: Rc<Path>,
+    defining_functions: Weak<RefCell<FunctionTable>>,
+    defining_modules: Weak<RefCell<ModuleTable>>,
+    /// Results by argument (see `memo_key`) once `memoize` has been called for
+    /// this definition; `None` while the function is not memoized.
+    memo: RefCell<Option<HashMap<String, Expression>>>,
 }
 
 #[derive(Clone)]
 struct ModuleState {
     functions: SharedFunctionTable,
+    // Owns the module's own import table; its functions only hold weak references.
+    _modules: SharedModuleTable,
 }
 
-type FunctionTable = HashMap<String, FunctionDefinition>;
+// Definitions are reference-counted so a call only bumps a counter instead of
+// cloning the function body out of the table.
+type FunctionTable = HashMap<Name, Rc<FunctionDefinition>>;
 type SharedFunctionTable = Rc<RefCell<FunctionTable>>;
 type NativeFunction = fn(Vec<Expression>) -> Option<Expression>;
 type NativeFunctionTable = Rc<HashMap<String, NativeFunction>>;
 type ModuleTable = HashMap<String, ModuleState>;
 type SharedModuleTable = Rc<RefCell<ModuleTable>>;
+type SharedCallCounts = Rc<RefCell<HashMap<String, usize>>>;
+
+/// Category of a runtime error, exposed to scripts as the `type` field of the
+/// value bound by `catch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Runtime,
+    Type,
+    UndefinedVariable,
+    UndefinedFunction,
+    Arity,
+    Native,
+    Io,
+}
+
+impl ErrorKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Runtime => "runtime_error",
+            Self::Type => "type_error",
+            Self::UndefinedVariable => "undefined_variable",
+            Self::UndefinedFunction => "undefined_function",
+            Self::Arity => "arity_error",
+            Self::Native => "native_error",
+            Self::Io => "io_error",
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct RuntimeError {
+    kind: ErrorKind,
     message: String,
+    /// Source line of the innermost statement that failed, once known.
+    line: Option<usize>,
+    /// User functions the error unwound through, innermost first.
+    call_stack: Vec<String>,
 }
 
 impl RuntimeError {
     pub fn new(message: impl Into<String>) -> Self {
+        Self::of_kind(ErrorKind::Runtime, message)
+    }
+
+    pub fn of_kind(kind: ErrorKind, message: impl Into<String>) -> Self {
         Self {
+            kind,
             message: message.into(),
+            line: None,
+            call_stack: Vec::new(),
         }
     }
+
+    pub fn type_error(message: impl Into<String>) -> Self {
+        Self::of_kind(ErrorKind::Type, message)
+    }
+
+    /// The value a `catch` block binds: an object with `type` and `message`.
+    fn to_value(&self) -> Expression {
+        let mut fields = ObjectMap::new();
+        fields.insert(
+            "type".to_string(),
+            Expression::StringLiteral(self.kind.name().to_string()),
+        );
+        fields.insert(
+            "message".to_string(),
+            Expression::StringLiteral(self.message.clone()),
+        );
+        Expression::Object(fields)
+    }
+
+    fn with_frame(mut self, function: &str) -> Self {
+        self.call_stack.push(function.to_string());
+        self
+    }
+
+    /// Records where the error happened. Statements call this as the error
+    /// unwinds, so only the innermost one sticks.
+    fn at_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+
+    /// The message, prefixed with its source line when known, followed by one
+    /// `at <function>` line per call frame, for reporting uncaught errors.
+    pub fn report(&self) -> String {
+        let mut report = match self.line {
+            Some(line) => format!("line {}: {}", line, self.message),
+            None => self.message.clone(),
+        };
+        for function in &self.call_stack {
+            report.push_str("\n  at ");
+            report.push_str(function);
+        }
+        report
+    }
 }
 
 impl fmt::Display for RuntimeError {
@@ -117,7 +218,9 @@ pub struct Runtime {
     native_functions: NativeFunctionTable,
     modules: SharedModuleTable,
     module_cache_by_path: SharedModuleTable,
-    base_dir: PathBuf,
+    base_dir: Rc<Path>,
+    trace: bool,
+    call_counts: Option<SharedCallCounts>,
 }
 
 impl Runtime {
@@ -133,13 +236,40 @@ impl Runtime {
             native_functions: Rc::new(HashMap::default()),
             modules: Rc::new(RefCell::new(HashMap::default())),
             module_cache_by_path: Rc::new(RefCell::new(HashMap::default())),
-            base_dir,
+            base_dir: Rc::from(base_dir),
+            trace: false,
+            call_counts: None,
         };
 
         runtime.register_native_functions();
         runtime
     }
 
+    pub(crate) fn set_trace(&mut self, enabled: bool) {
+        self.trace = enabled;
+    }
+
+    pub(crate) fn enable_profiling(&mut self) {
+        self.call_counts = Some(Rc::new(RefCell::new(HashMap::default())));
+    }
+
+    /// Returns function call counts sorted from most to least frequently called.
+    pub(crate) fn profile_report(&self) -> Vec<(String, usize)> {
+        let Some(call_counts) = &self.call_counts else {
+            return Vec::new();
+        };
+
+        let mut report: Vec<(String, usize)> = call_counts
+            .borrow()
+            .iter()
+            .map(|(name, count)| (name.clone(), *count))
+            .collect();
+        report.sort_by(|(a_name, a_count), (b_name, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+        });
+        report
+    }
+
     fn register_native_functions(&mut self) {
         let mut map = HashMap::default();
         for (name, func) in get_all_native_functions() {
@@ -150,30 +280,230 @@ impl Runtime {
 
     fn define_function(
         &self,
-        name: String,
-        params: Vec<String>,
-        body: Vec<Statement>,
+        name: Name,
+        params: Vec<Name>,
+        body: Vec<Spanned<Statement>>,
         exported: bool,
     ) {
-        self.functions.borrow_mut().insert(
-            name,
-            FunctionDefinition {
-                params,
-                body,
-                exported,
-                scope: self.scope.clone(),
-                base_dir: self.base_dir.clone(),
-            },
-        );
+        let definition = self.new_definition(params, body, exported);
+        self.functions.borrow_mut().insert(name, definition);
+    }
+
+    /// Builds a definition bound to this runtime's scope and tables.
+    fn new_definition(
+        &self,
+        params: Vec<Name>,
+        body: Vec<Spanned<Statement>>,
+        exported: bool,
+    ) -> Rc<FunctionDefinition> {
+        Rc::new(FunctionDefinition {
+            params,
+            body,
+            exported,
+            scope: self.scope.clone(),
+            base_dir: self.base_dir.clone(),
+            defining_functions: Rc::downgrade(&self.functions),
+            defining_modules: Rc::downgrade(&self.modules),
+            memo: RefCell::new(None),
+        })
     }
 
-    fn assign_variable(&self, name: String, value: Expression) {
-        let target_scope = VariableScope::find_containing(&self.scope, &name)
-            .unwrap_or_else(|| self.scope.clone());
-        target_scope.variables.borrow_mut().insert(name, value);
+    /// Starts caching results of the named 1-arg function, keyed by argument.
+    pub(super) fn memoize_function(&self, name: &str) -> Result<(), RuntimeError> {
+        let definition = self.functions.borrow().get(name).cloned().ok_or_else(|| {
+            RuntimeError::of_kind(
+                ErrorKind::UndefinedFunction,
+                format!("Unknown function '{}'", name),
+            )
+        })?;
+        if definition.params.len() != 1 {
+            return Err(RuntimeError::new(format!(
+                "memoize only supports 1-argument functions, but '{}' takes {}",
+                name,
+                definition.params.len()
+            )));
+        }
+
+        definition
+            .memo
+            .borrow_mut()
+            .get_or_insert_with(HashMap::default);
+        Ok(())
     }
 
-    fn set_local_variable(&self, name: String, value: Expression) -> Option<Expression> {
+    /// Copies the visible variables and this file's functions so a worker thread
+    /// can rebuild an equivalent runtime. Functions imported from modules bring
+    /// their defining module's functions and variables along, so they can still
+    /// call that module's private helpers.
+    pub(super) fn worker_snapshot(&self) -> WorkerSnapshot {
+        let mut snapshot = WorkerSnapshot {
+            modules: vec![ModuleSnapshot {
+                cache_key: None,
+                functions: Vec::new(),
+                variables: Self::visible_variables(&self.scope),
+                base_dir: self.base_dir.to_path_buf(),
+            }],
+            definitions: Vec::new(),
+        };
+        let mut tables = vec![self.functions.clone()];
+        let mut table_indices: HashMap<*const RefCell<FunctionTable>, usize> = HashMap::default();
+        table_indices.insert(Rc::as_ptr(&self.functions), 0);
+        let mut definition_indices: HashMap<*const FunctionDefinition, usize> = HashMap::default();
+
+        // Tables are appended as imported definitions reveal their modules, so
+        // this walks every module reachable from the caller's functions.
+        let mut next_table = 0;
+        while next_table < tables.len() {
+            let entries: Vec<(Name, Rc<FunctionDefinition>)> = tables[next_table]
+                .borrow()
+                .iter()
+                .map(|(name, definition)| (name.clone(), definition.clone()))
+                .collect();
+            for (name, definition) in entries {
+                let index = match definition_indices.get(&Rc::as_ptr(&definition)) {
+                    Some(index) => *index,
+                    None => {
+                        let module = definition.defining_functions.upgrade().map(|table| {
+                            *table_indices.entry(Rc::as_ptr(&table)).or_insert_with(|| {
+                                snapshot.modules.push(ModuleSnapshot {
+                                    cache_key: self.module_cache_key(&table),
+                                    functions: Vec::new(),
+                                    variables: Self::visible_variables(&definition.scope),
+                                    base_dir: definition.base_dir.to_path_buf(),
+                                });
+                                tables.push(table);
+                                tables.len() - 1
+                            })
+                        });
+                        snapshot.definitions.push(FunctionSnapshot {
+                            params: definition.params.clone(),
+                            body: definition.body.clone(),
+                            exported: definition.exported,
+                            module,
+                        });
+                        definition_indices
+                            .insert(Rc::as_ptr(&definition), snapshot.definitions.len() - 1);
+                        snapshot.definitions.len() - 1
+                    }
+                };
+                snapshot.modules[next_table].functions.push((name, index));
+            }
+            next_table += 1;
+        }
+        snapshot
+    }
+
+    /// Values of every variable visible from `scope`, innermost binding first.
+    fn visible_variables(scope: &Rc<VariableScope>) -> Vec<(Name, Expression)> {
+        let mut variables: VariableTable = HashMap::default();
+        let mut scope = Some(scope);
+        while let Some(current) = scope {
+            for (name, value) in current.variables.borrow().iter() {
+                variables
+                    .entry(name.clone())
+                    .or_insert_with(|| value.clone());
+            }
+            scope = current.parent.as_ref();
+        }
+        variables.into_iter().collect()
+    }
+
+    /// The path a loaded module is cached under, found by its function table.
+    fn module_cache_key(&self, table: &SharedFunctionTable) -> Option<String> {
+        self.module_cache_by_path
+            .borrow()
+            .iter()
+            .find(|(_, state)| Rc::ptr_eq(&state.functions, table))
+            .map(|(key, _)| key.clone())
+    }
+
+    pub(super) fn from_worker_snapshot(snapshot: &WorkerSnapshot) -> Self {
+        let runtimes: Vec<Runtime> = snapshot
+            .modules
+            .iter()
+            .map(|module| {
+                let runtime = Self::new_with_base_dir(module.base_dir.clone());
+                for (name, value) in &module.variables {
+                    runtime.set_local_variable(name.clone(), value.clone());
+                }
+                runtime
+            })
+            .collect();
+
+        // A definition whose module was already unloaded keeps a dead link, so
+        // calling it fails the same way it would on the caller's thread.
+        let definitions: Vec<Rc<FunctionDefinition>> = snapshot
+            .definitions
+            .iter()
+            .map(|function| {
+                let params = function.params.clone();
+                let body = function.body.clone();
+                match function.module {
+                    Some(module) => {
+                        runtimes[module].new_definition(params, body, function.exported)
+                    }
+                    None => {
+                        let definition =
+                            runtimes[0].new_definition(params, body, function.exported);
+                        Rc::new(FunctionDefinition {
+                            defining_functions: Weak::new(),
+                            defining_modules: Weak::new(),
+                            ..(*definition).clone()
+                        })
+                    }
+                }
+            })
+            .collect();
+
+        let mut runtimes = runtimes.into_iter().zip(&snapshot.modules);
+        let (runtime, module) = runtimes
+            .next()
+            .expect("the caller's module is always first");
+        for (name, index) in &module.functions {
+            runtime
+                .functions
+                .borrow_mut()
+                .insert(name.clone(), definitions[*index].clone());
+        }
+        // Definitions only hold weak links to their module's tables, so the
+        // rebuilt modules are kept alive in the worker's module cache.
+        for (index, (module_runtime, module)) in runtimes.enumerate() {
+            for (name, definition) in &module.functions {
+                module_runtime
+                    .functions
+                    .borrow_mut()
+                    .insert(name.clone(), definitions[*definition].clone());
+            }
+            let cache_key = module
+                .cache_key
+                .clone()
+                .unwrap_or_else(|| format!("<worker module {}>", index + 1));
+            runtime.module_cache_by_path.borrow_mut().insert(
+                cache_key,
+                ModuleState {
+                    functions: module_runtime.functions.clone(),
+                    _modules: module_runtime.modules.clone(),
+                },
+            );
+        }
+        runtime
+    }
+
+    fn assign_variable(&self, name: &Name, value: Expression) {
+        if let Some(slot) = self.scope.variables.borrow_mut().get_mut(&**name) {
+            *slot = value;
+            return;
+        }
+
+        let target_scope =
+            VariableScope::find_containing(&self.scope, name).unwrap_or_else(|| self.scope.clone());
+        target_scope
+            .variables
+            .borrow_mut()
+            .insert(name.clone(), value);
+    }
+
+    fn set_local_variable(&self, name: Name, value: Expression) -> Option<Expression> {
         self.scope.variables.borrow_mut().insert(name, value)
     }
 
@@ -181,15 +511,85 @@ impl Runtime {
         self.scope.variables.borrow_mut().remove(name)
     }
 
-    fn lookup_variable(&self, name: &str) -> Option<Expression> {
+    pub(super) fn lookup_variable(&self, name: &str) -> Option<Expression> {
         VariableScope::lookup(&self.scope, name)
     }
 
+    /// Reads a variable, reporting a missing one as a catchable runtime error.
+    fn require_variable(&self, name: &str) -> Result<Expression, RuntimeError> {
+        self.lookup_variable(name)
+            .ok_or_else(|| Self::undefined_variable(name))
+    }
+
+    /// Removes a variable from the nearest scope that defines it.
+    fn unset_variable(&self, name: &str) -> Result<(), RuntimeError> {
+        let scope = VariableScope::find_containing(&self.scope, name)
+            .ok_or_else(|| Self::undefined_variable(name))?;
+        scope.variables.borrow_mut().remove(name);
+        Ok(())
+    }
+
+    fn undefined_variable(name: &str) -> RuntimeError {
+        RuntimeError::of_kind(
+            ErrorKind::UndefinedVariable,
+            format!("Undefined variable '{}'", name),
+        )
+    }
+
     pub(crate) fn execute(
         &mut self,
-        statements: &[Statement],
+        statements: &[Spanned<Statement>],
     ) -> Result<Option<Expression>, RuntimeError> {
-        match self.execute_with_signal(statements)? {
+        let signal = self.execute_with_signal(statements)?;
+        Self::block_result(signal)
+    }
+
+    /// Yields `start`, `start + step`, ... up to and including `end`. A
+    /// negative step counts down.
+    fn range_values(
+        start: Expression,
+        end: Expression,
+        step: Option<Expression>,
+    ) -> Result<impl Iterator<Item = i32>, RuntimeError> {
+        let (Expression::Number(start), Expression::Number(end)) = (start, end) else {
+            return Err(RuntimeError::type_error(
+                "for range bounds must be integers",
+            ));
+        };
+        let step = match step {
+            None => 1,
+            Some(Expression::Number(step)) if step != 0 => step,
+            Some(_) => {
+                return Err(RuntimeError::new(
+                    "for range step must be a non-zero integer",
+                ))
+            }
+        };
+
+        Ok(
+            std::iter::successors(Some(start), move |value| value.checked_add(step)).take_while(
+                move |value| {
+                    if step > 0 {
+                        *value <= end
+                    } else {
+                        *value >= end
+                    }
+                },
+            ),
+        )
+    }
+
+    fn repeat_count(value: Expression) -> Result<usize, RuntimeError> {
+        match value {
+            Expression::Number(count) if count >= 0 => Ok(count as usize),
+            _ => Err(RuntimeError::type_error(
+                "repeat count must evaluate to a non-negative integer",
+            )),
+        }
+    }
+
+    fn block_result(signal: FlowSignal) -> Result<Option<Expression>, RuntimeError> {
+        match signal {
             FlowSignal::None => Ok(None),
             FlowSignal::Return(value) => Ok(Some(value)),
             FlowSignal::Break => Err(RuntimeError::new("break can only be used inside a loop")),
@@ -199,15 +599,21 @@ impl Runtime {
         }
     }
 
-    pub(crate) fn execute_tests(&mut self, statements: &[Statement]) -> Result<(), RuntimeError> {
+    pub(crate) fn execute_tests(
+        &mut self,
+        statements: &[Spanned<Statement>],
+    ) -> Result<(), RuntimeError> {
         self.prepare_test_runtime(statements)?;
+        let mut failed_tests = Vec::new();
 
         for statement in statements {
-            if let Statement::Test { name, body } = statement {
+            if let Statement::Test { name, body } = &statement.node {
                 println!("Running test: {}", name);
+                let failures_before = assertion_failures();
                 let mut nested_runtime = self.create_nested_runtime(
                     HashMap::default(),
                     self.functions.clone(),
+                    self.modules.clone(),
                     self.scope.clone(),
                     self.base_dir.clone(),
                 );
@@ -225,16 +631,33 @@ impl Runtime {
                         return Err(RuntimeError::new("continue can only be used inside a loop"));
                     }
                 }
-                println!("Test '{}' finished", name);
+                let failures = assertion_failures() - failures_before;
+                if failures > 0 {
+                    println!("Test '{}' failed ({} assertion(s) failed)", name, failures);
+                    failed_tests.push(name.clone());
+                } else {
+                    println!("Test '{}' finished", name);
+                }
             }
         }
 
+        if !failed_tests.is_empty() {
+            return Err(RuntimeError::new(format!(
+                "{} test(s) failed: {}",
+                failed_tests.len(),
+                failed_tests.join(", ")
+            )));
+        }
+
         Ok(())
     }
 
-    fn prepare_test_runtime(&mut self, statements: &[Statement]) -> Result<(), RuntimeError> {
+    fn prepare_test_runtime(
+        &mut self,
+        statements: &[Spanned<Statement>],
+    ) -> Result<(), RuntimeError> {
         for statement in statements {
-            match statement {
+            match &statement.node {
                 Statement::Function {
                     name,
                     params,
@@ -249,6 +672,13 @@ impl Runtime {
                 } => {
                     self.import_module(module_name, file_path)?;
                 }
+                Statement::ImportFunctions {
+                    names,
+                    file_path,
+                    exported,
+                } => {
+                    self.import_functions(names, file_path, *exported)?;
+                }
                 _ => {}
             }
         }
@@ -258,166 +688,329 @@ impl Runtime {
 
     fn execute_with_signal(
         &mut self,
-        statements: &[Statement],
+        statements: &[Spanned<Statement>],
     ) -> Result<FlowSignal, RuntimeError> {
         for statement in statements {
-            match statement {
-                Statement::PropertySet {
-                    object,
-                    property,
-                    value,
-                } => {
-                    let evaluated_value = self.evaluate_expression(value.clone())?;
-                    self.assign_property(object.clone(), property.clone(), evaluated_value)?;
-                }
-                Statement::ForLoop {
-                    variable,
-                    iterable,
-                    body,
-                } => {
-                    let iterable_value = self.evaluate_expression(iterable.clone())?;
-                    let Expression::Array(elements) = iterable_value else {
-                        return Err(RuntimeError::new(
-                            "for loop iterable must evaluate to an array",
-                        ));
-                    };
-
-                    for element in elements {
-                        self.assign_variable(variable.clone(), element);
-                        match self.execute_with_signal(body)? {
-                            FlowSignal::None => {}
-                            FlowSignal::Continue => continue,
-                            FlowSignal::Break => break,
-                            FlowSignal::Return(value) => return Ok(FlowSignal::Return(value)),
-                        }
-                    }
-                }
-                Statement::WhileLoop { condition, body } => loop {
-                    let cond_value = self.evaluate_expression(condition.clone())?;
-                    match cond_value {
-                        Expression::Boolean(true) => match self.execute_with_signal(body)? {
-                            FlowSignal::None => {}
-                            FlowSignal::Continue => continue,
-                            FlowSignal::Break => break,
-                            FlowSignal::Return(value) => {
-                                return Ok(FlowSignal::Return(value));
-                            }
-                        },
-                        Expression::Boolean(false) => break,
-                        _ => {
-                            return Err(RuntimeError::new(
-                                "while condition must evaluate to a boolean",
-                            ));
-                        }
+            if self.trace {
+                eprintln!(
+                    "[trace] line {}: {}",
+                    statement.line,
+                    render_statement(&statement.node)
+                );
+            }
+
+            match self
+                .execute_statement(&statement.node)
+                .map_err(|err| err.at_line(statement.line))?
+            {
+                FlowSignal::None => {}
+                signal => return Ok(signal),
+            }
+        }
+        Ok(FlowSignal::None)
+    }
+
+    /// Runs one statement. `FlowSignal::None` means execution carries on with the
+    /// next statement of the enclosing block.
+    ///
+    /// Every call into a user function passes through this frame, so statements
+    /// that run nested blocks live in their own methods to keep it small.
+    fn execute_statement(&mut self, statement: &Statement) -> Result<FlowSignal, RuntimeError> {
+        match statement {
+            Statement::PropertySet {
+                object,
+                property,
+                value,
+            } => {
+                let evaluated_value = self.evaluate_expression(value)?;
+                self.assign_property(object, property, evaluated_value)?;
+            }
+            Statement::ForLoop {
+                variable,
+                iterable,
+                body,
+            } => return self.execute_for_loop(variable, iterable, body),
+            Statement::RangeLoop {
+                variable,
+                start,
+                end,
+                step,
+                body,
+            } => return self.execute_range_loop(variable, start, end, step.as_ref(), body),
+            Statement::WhileLoop { condition, body } => {
+                return self.execute_while_loop(condition, body)
+            }
+            Statement::Repeat { count_expr, body } => return self.execute_repeat(count_expr, body),
+            Statement::Set { var, value } => {
+                let evaluated_value = self.evaluate_expression(value)?;
+                self.assign_variable(var, evaluated_value);
+            }
+            Statement::Function {
+                name,
+                params,
+                body,
+                exported,
+            } => {
+                self.define_function(name.clone(), params.clone(), body.clone(), *exported);
+            }
+            Statement::FunctionCall { name, args } => {
+                self.call_named_function(name, args)?;
+            }
+            Statement::Discard { expr } => {
+                self.evaluate_expression(expr)?;
+            }
+            Statement::Unset { var } => self.unset_variable(var)?,
+            Statement::Print { expr } => {
+                let value = self.evaluate_expression(expr)?;
+                self.print_expression(&value)?;
+            }
+            Statement::Return { expr } => {
+                return Ok(FlowSignal::Return(self.evaluate_expression(expr)?));
+            }
+            Statement::If {
+                condition,
+                body,
+                else_body,
+            } => return self.execute_if(condition, body, else_body.as_deref()),
+            Statement::Import {
+                module_name,
+                file_path,
+            } => {
+                self.import_module(module_name, file_path)?;
+            }
+            Statement::ImportFunctions {
+                names,
+                file_path,
+                exported,
+            } => {
+                self.import_functions(names, file_path, *exported)?;
+            }
+            Statement::Test { .. } => {}
+            Statement::Benchmark { label, body } => return self.execute_benchmark(label, body),
+            Statement::Break => return Ok(FlowSignal::Break),
+            Statement::Continue => return Ok(FlowSignal::Continue),
+            Statement::TryCatch {
+                try_body,
+                error_var,
+                catch_body,
+            } => return self.execute_try_catch(try_body, error_var, catch_body),
+        }
+        Ok(FlowSignal::None)
+    }
+
+    fn execute_for_loop(
+        &mut self,
+        variable: &Name,
+        iterable: &Expression,
+        body: &[Spanned<Statement>],
+    ) -> Result<FlowSignal, RuntimeError> {
+        let iterable_value = self.evaluate_expression(iterable)?;
+        let Expression::Array(elements) = iterable_value else {
+            return Err(RuntimeError::type_error(
+                "for loop iterable must evaluate to an array",
+            ));
+        };
+
+        for element in elements {
+            self.assign_variable(variable, element);
+            match self.execute_with_signal(body)? {
+                FlowSignal::None => {}
+                FlowSignal::Continue => continue,
+                FlowSignal::Break => break,
+                FlowSignal::Return(value) => return Ok(FlowSignal::Return(value)),
+            }
+        }
+        Ok(FlowSignal::None)
+    }
+
+    fn execute_range_loop(
+        &mut self,
+        variable: &Name,
+        start: &Expression,
+        end: &Expression,
+        step: Option<&Expression>,
+        body: &[Spanned<Statement>],
+    ) -> Result<FlowSignal, RuntimeError> {
+        let step = match step {
+            Some(step) => Some(self.evaluate_expression(step)?),
+            None => None,
+        };
+        let values = Self::range_values(
+            self.evaluate_expression(start)?,
+            self.evaluate_expression(end)?,
+            step,
+        )?;
+        for value in values {
+            self.assign_variable(variable, Expression::Number(value));
+            match self.execute_with_signal(body)? {
+                FlowSignal::None | FlowSignal::Continue => {}
+                FlowSignal::Break => break,
+                signal => return Ok(signal),
+            }
+        }
+        Ok(FlowSignal::None)
+    }
+
+    fn execute_while_loop(
+        &mut self,
+        condition: &Expression,
+        body: &[Spanned<Statement>],
+    ) -> Result<FlowSignal, RuntimeError> {
+        loop {
+            let cond_value = self.evaluate_expression(condition)?;
+            match cond_value {
+                Expression::Boolean(true) => match self.execute_with_signal(body)? {
+                    FlowSignal::None => {}
+                    FlowSignal::Continue => continue,
+                    FlowSignal::Break => break,
+                    FlowSignal::Return(value) => {
+                        return Ok(FlowSignal::Return(value));
                     }
                 },
-                Statement::Set { var, value } => {
-                    let evaluated_value = self.evaluate_expression(value.clone())?;
-                    self.assign_variable(var.clone(), evaluated_value);
-                }
-                Statement::Function {
-                    name,
-                    params,
-                    body,
-                    exported,
-                } => {
-                    self.define_function(name.clone(), params.clone(), body.clone(), *exported);
-                }
-                Statement::FunctionCall { name, args } => {
-                    let value = self.evaluate_expression(Expression::FunctionCall {
-                        name: name.clone(),
-                        args: args.clone(),
-                    })?;
-                    self.print_expression(&value)?;
-                }
-                Statement::Print { expr } => {
-                    let value = self.evaluate_expression(expr.clone())?;
-                    self.print_expression(&value)?;
-                }
-                Statement::Return { expr } => {
-                    return Ok(FlowSignal::Return(self.evaluate_expression(expr.clone())?));
-                }
-                Statement::If {
-                    condition,
-                    body,
-                    else_body,
-                } => {
-                    let cond_value = self.evaluate_expression(condition.clone())?;
-                    match cond_value {
-                        Expression::Boolean(true) => match self.execute_with_signal(body)? {
-                            FlowSignal::None => {}
-                            signal => return Ok(signal),
-                        },
-                        Expression::Boolean(false) => {
-                            if let Some(else_body) = else_body {
-                                match self.execute_with_signal(else_body)? {
-                                    FlowSignal::None => {}
-                                    signal => return Ok(signal),
-                                }
-                            }
-                        }
-                        _ => {
-                            return Err(RuntimeError::new(
-                                "if condition must evaluate to a boolean",
-                            ));
-                        }
-                    }
-                }
-                Statement::Import {
-                    module_name,
-                    file_path,
-                } => {
-                    self.import_module(module_name, file_path)?;
+                Expression::Boolean(false) => break,
+                _ => {
+                    return Err(RuntimeError::type_error(
+                        "while condition must evaluate to a boolean",
+                    ));
                 }
-                Statement::Test { .. } => {}
-                Statement::Break => return Ok(FlowSignal::Break),
-                Statement::Continue => return Ok(FlowSignal::Continue),
-                Statement::TryCatch {
-                    try_body,
-                    error_var,
-                    catch_body,
-                } => match self.execute_with_signal(try_body) {
-                    Ok(FlowSignal::None) => {}
-                    Ok(signal) => return Ok(signal),
-                    Err(error) => {
-                        let previous_value = self.set_local_variable(
-                            error_var.clone(),
-                            Expression::StringLiteral(error.to_string()),
-                        );
-                        let catch_result = self.execute_with_signal(catch_body);
-                        if let Some(value) = previous_value {
-                            self.set_local_variable(error_var.clone(), value);
-                        } else {
-                            self.remove_local_variable(error_var);
-                        }
-                        match catch_result? {
-                            FlowSignal::None => {}
-                            signal => return Ok(signal),
-                        }
-                    }
-                },
             }
         }
         Ok(FlowSignal::None)
     }
 
+    fn execute_repeat(
+        &mut self,
+        count_expr: &Expression,
+        body: &[Spanned<Statement>],
+    ) -> Result<FlowSignal, RuntimeError> {
+        let count = Self::repeat_count(self.evaluate_expression(count_expr)?)?;
+        for _ in 0..count {
+            match self.execute_with_signal(body)? {
+                FlowSignal::None | FlowSignal::Continue => {}
+                FlowSignal::Break => break,
+                signal => return Ok(signal),
+            }
+        }
+        Ok(FlowSignal::None)
+    }
+
+    fn execute_if(
+        &mut self,
+        condition: &Expression,
+        body: &[Spanned<Statement>],
+        else_body: Option<&[Spanned<Statement>]>,
+    ) -> Result<FlowSignal, RuntimeError> {
+        let cond_value = self.evaluate_expression(condition)?;
+        match cond_value {
+            Expression::Boolean(true) => self.execute_with_signal(body),
+            Expression::Boolean(false) => match else_body {
+                Some(else_body) => self.execute_with_signal(else_body),
+                None => Ok(FlowSignal::None),
+            },
+            _ => Err(RuntimeError::type_error(
+                "if condition must evaluate to a boolean",
+            )),
+        }
+    }
+
+    fn execute_benchmark(
+        &mut self,
+        label: &str,
+        body: &[Spanned<Statement>],
+    ) -> Result<FlowSignal, RuntimeError> {
+        let started = Instant::now();
+        let signal = self.execute_with_signal(body)?;
+        println!(
+            "Benchmark '{}' took {:.3} ms",
+            label,
+            started.elapsed().as_secs_f64() * 1000.0
+        );
+        Ok(signal)
+    }
+
+    fn execute_try_catch(
+        &mut self,
+        try_body: &[Spanned<Statement>],
+        error_var: &Name,
+        catch_body: &[Spanned<Statement>],
+    ) -> Result<FlowSignal, RuntimeError> {
+        match self.execute_with_signal(try_body) {
+            Err(error) => {
+                let previous_value = self.set_local_variable(error_var.clone(), error.to_value());
+                let catch_result = self.execute_with_signal(catch_body);
+                if let Some(value) = previous_value {
+                    self.set_local_variable(error_var.clone(), value);
+                } else {
+                    self.remove_local_variable(error_var);
+                }
+                catch_result
+            }
+            result => result,
+        }
+    }
+
     fn import_module(&mut self, module_name: &str, file_path: &str) -> Result<(), RuntimeError> {
+        let module_state = self.load_module(file_path)?;
+        self.modules
+            .borrow_mut()
+            .insert(module_name.to_string(), module_state);
+        Ok(())
+    }
+
+    fn import_functions(
+        &mut self,
+        names: &[String],
+        file_path: &str,
+        exported: bool,
+    ) -> Result<(), RuntimeError> {
+        let module_state = self.load_module(file_path)?;
+
+        for name in names {
+            let definition = module_state.functions.borrow().get(name.as_str()).cloned();
+            let Some(definition) = definition else {
+                return Err(RuntimeError::new(format!(
+                    "Module '{}' has no function '{}'",
+                    file_path, name
+                )));
+            };
+            if !definition.exported {
+                return Err(RuntimeError::new(format!(
+                    "Function '{}' is not exported by '{}'",
+                    name, file_path
+                )));
+            }
+
+            let definition = if definition.exported == exported {
+                definition
+            } else {
+                Rc::new(FunctionDefinition {
+                    exported,
+                    ..(*definition).clone()
+                })
+            };
+            self.functions.borrow_mut().insert(intern(name), definition);
+        }
+
+        Ok(())
+    }
+
+    fn load_module(&mut self, file_path: &str) -> Result<ModuleState, RuntimeError> {
         let resolved_path = self.resolve_import_path(file_path)?;
         let cache_key = resolved_path.to_string_lossy().to_string();
 
         if let Some(cached) = self.module_cache_by_path.borrow().get(&cache_key).cloned() {
-            self.modules
-                .borrow_mut()
-                .insert(module_name.to_string(), cached);
-            return Ok(());
+            return Ok(cached);
         }
 
         let content = fs::read_to_string(&resolved_path).map_err(|e| {
-            RuntimeError::new(format!(
-                "Error loading module from '{}': {}",
-                resolved_path.display(),
-                e
-            ))
+            RuntimeError::of_kind(
+                ErrorKind::Io,
+                format!(
+                    "Error loading module from '{}': {}",
+                    resolved_path.display(),
+                    e
+                ),
+            )
         })?;
 
         let module_statements =
@@ -425,23 +1018,30 @@ impl Runtime {
 
         let module_base_dir = resolved_path
             .parent()
-            .map(Path::to_path_buf)
-            .unwrap_or_else(|| self.base_dir.clone());
+            .unwrap_or(&self.base_dir)
+            .to_path_buf();
 
         let mut module_runtime = Runtime::new_with_base_dir(module_base_dir);
-        module_runtime.execute(&module_statements)?;
+        module_runtime.module_cache_by_path = self.module_cache_by_path.clone();
+        module_runtime.trace = self.trace;
+        module_runtime.call_counts = self.call_counts.clone();
 
+        // Register the module before running it so cyclic imports resolve to the
+        // partially loaded function table instead of loading the file again.
         let module_state = ModuleState {
             functions: module_runtime.functions.clone(),
+            _modules: module_runtime.modules.clone(),
         };
-        self.modules
-            .borrow_mut()
-            .insert(module_name.to_string(), module_state.clone());
         self.module_cache_by_path
             .borrow_mut()
-            .insert(cache_key, module_state);
+            .insert(cache_key.clone(), module_state.clone());
 
-        Ok(())
+        if let Err(error) = module_runtime.execute(&module_statements) {
+            self.module_cache_by_path.borrow_mut().remove(&cache_key);
+            return Err(error);
+        }
+
+        Ok(module_state)
     }
 
     fn resolve_import_path(&self, file_path: &str) -> Result<PathBuf, RuntimeError> {
@@ -462,10 +1062,11 @@ impl Runtime {
             match part {
                 InterpolationPart::Text(text) => rendered.push_str(text),
                 InterpolationPart::Expression(expr) => {
-                    let evaluated = self.evaluate_expression(expr.clone())?;
+                    let evaluated = self.evaluate_expression(expr)?;
                     match evaluated {
                         Expression::StringLiteral(s) => rendered.push_str(&s),
                         Expression::Number(n) => rendered.push_str(&n.to_string()),
+                        Expression::Float(n) => rendered.push_str(&format_float(n)),
                         Expression::Boolean(b) => rendered.push_str(&b.to_string()),
                         Expression::Null => rendered.push_str("null"),
                         _ => rendered.push_str(&self.expression_to_string(&evaluated)?),
@@ -479,17 +1080,20 @@ impl Runtime {
 
     fn bind_arguments(
         &self,
-        params: &[String],
+        params: &[Name],
         args: &[Expression],
         function_name: &str,
-    ) -> Result<HashMap<String, Expression>, RuntimeError> {
+    ) -> Result<VariableTable, RuntimeError> {
         if params.len() != args.len() {
-            return Err(RuntimeError::new(format!(
-                "Function '{}' expected {} arguments but received {}",
-                function_name,
-                params.len(),
-                args.len()
-            )));
+            return Err(RuntimeError::of_kind(
+                ErrorKind::Arity,
+                format!(
+                    "Function '{}' expected {} arguments but received {}",
+                    function_name,
+                    params.len(),
+                    args.len()
+                ),
+            ));
         }
 
         let mut locals = HashMap::default();
@@ -501,24 +1105,106 @@ impl Runtime {
 
     fn create_nested_runtime(
         &self,
-        variables: HashMap<String, Expression>,
+        variables: VariableTable,
         functions: SharedFunctionTable,
+        modules: SharedModuleTable,
         parent_scope: Rc<VariableScope>,
-        base_dir: PathBuf,
+        base_dir: Rc<Path>,
     ) -> Runtime {
         Runtime {
             scope: VariableScope::child(variables, parent_scope),
             functions,
             native_functions: self.native_functions.clone(),
-            modules: self.modules.clone(),
+            modules,
             module_cache_by_path: self.module_cache_by_path.clone(),
             base_dir,
+            trace: self.trace,
+            call_counts: self.call_counts.clone(),
+        }
+    }
+
+    /// A runtime over the same scope and tables, so statements it runs read and
+    /// update the caller's variables. Used by `breakpoint`.
+    pub(super) fn shared_scope_runtime(&self) -> Runtime {
+        Runtime {
+            scope: self.scope.clone(),
+            functions: self.functions.clone(),
+            native_functions: self.native_functions.clone(),
+            modules: self.modules.clone(),
+            module_cache_by_path: self.module_cache_by_path.clone(),
+            base_dir: self.base_dir.clone(),
+            trace: self.trace,
+            call_counts: self.call_counts.clone(),
+        }
+    }
+
+    fn invoke_function(
+        &self,
+        name: &str,
+        definition: &FunctionDefinition,
+        args: &[Expression],
+    ) -> Result<Expression, RuntimeError> {
+        let memo_key = match (definition.memo.borrow().as_ref(), args) {
+            (Some(memo), [arg]) => {
+                let key = Self::memo_key(arg);
+                if let Some(cached) = memo.get(&key) {
+                    return Ok(cached.clone());
+                }
+                Some(key)
+            }
+            _ => None,
+        };
+
+        let value = self.run_function_body(name, definition, args)?;
+        if let (Some(key), Some(memo)) = (memo_key, definition.memo.borrow_mut().as_mut()) {
+            memo.insert(key, value.clone());
         }
+        Ok(value)
+    }
+
+    /// Cache key for a memoized argument. The debug form names every variant, so
+    /// values that print alike, such as `3`, `3.0` and `"3"`, get distinct keys.
+    fn memo_key(arg: &Expression) -> String {
+        format!("{:?}", arg)
+    }
+
+    fn run_function_body(
+        &self,
+        name: &str,
+        definition: &FunctionDefinition,
+        args: &[Expression],
+    ) -> Result<Expression, RuntimeError> {
+        let local_vars = self.bind_arguments(&definition.params, args, name)?;
+        // Run the body against the tables of the file that defined the function so
+        // module-private helpers and the module's own imports stay reachable.
+        // Falling back to the caller's tables would resolve names in the wrong
+        // file, so a definition whose file is gone cannot run.
+        let (Some(functions), Some(modules)) = (
+            definition.defining_functions.upgrade(),
+            definition.defining_modules.upgrade(),
+        ) else {
+            return Err(RuntimeError::new(format!(
+                "Function '{}' can no longer be called: the file that defined it is no longer loaded",
+                name
+            )));
+        };
+        let mut nested_runtime = self.create_nested_runtime(
+            local_vars,
+            functions,
+            modules,
+            definition.scope.clone(),
+            definition.base_dir.clone(),
+        );
+        let value = nested_runtime
+            .execute(&definition.body)
+            .map_err(|err| err.with_frame(name))?;
+        Ok(value.unwrap_or(Expression::Null))
     }
 
     fn print_expression(&self, expr: &Expression) -> Result<(), RuntimeError> {
         match expr {
             Expression::Number(n) => println!("{}", n),
+            Expression::Float(n) => println!("{}", format_float(*n)),
             Expression::Boolean(b) => println!("{}", b),
             Expression::StringLiteral(s) => println!("{}", s),
             Expression::StringInterpolation { parts } => {
@@ -533,7 +1219,7 @@ impl Runtime {
                     .collect::<Result<Vec<_>, _>>()?;
                 println!("[{}]", elements.join(", "));
             }
-            Expression::Object(properties) => {
+            Expression::Object(properties) | Expression::FrozenObject(properties) => {
                 let elements: Vec<String> = properties
                     .iter()
                     .map(|(key, value)| {
@@ -543,21 +1229,17 @@ impl Runtime {
                     .collect::<Result<Vec<_>, _>>()?;
                 println!("{{{}}}", elements.join(", "));
             }
+            Expression::Map(_) => println!("{}", self.expression_to_string(expr)?),
             Expression::Variable(name) => {
-                let val = self
-                    .lookup_variable(name)
-                    .ok_or_else(|| RuntimeError::new(format!("Undefined variable '{}'", name)))?;
+                let val = self.require_variable(name)?;
                 self.print_expression(&val)?;
             }
-            Expression::FunctionCall { name, args } => {
-                let val = self.evaluate_expression(Expression::FunctionCall {
-                    name: name.clone(),
-                    args: args.clone(),
-                })?;
+            Expression::FunctionCall { .. } => {
+                let val = self.evaluate_expression(expr)?;
                 self.print_expression(&val)?;
             }
             Expression::BinaryOperation { .. } | Expression::UnaryOperation { .. } => {
-                let val = self.evaluate_expression(expr.clone())?;
+                let val = self.evaluate_expression(expr)?;
                 self.print_expression(&val)?;
             }
             _ => println!(),
@@ -568,6 +1250,7 @@ impl Runtime {
     fn expression_to_string(&self, expr: &Expression) -> Result<String, RuntimeError> {
         match expr {
             Expression::Number(n) => Ok(n.to_string()),
+            Expression::Float(n) => Ok(format_float(*n)),
             Expression::Boolean(b) => Ok(b.to_string()),
             Expression::StringLiteral(s) => Ok(format!("\"{}\"", s)),
             Expression::StringInterpolation { parts } => {
@@ -582,7 +1265,7 @@ impl Runtime {
                     .collect::<Result<Vec<_>, _>>()?;
                 Ok(format!("[{}]", elements.join(", ")))
             }
-            Expression::Object(properties) => {
+            Expression::Object(properties) | Expression::FrozenObject(properties) => {
                 let elements: Vec<String> = properties
                     .iter()
                     .map(|(key, value)| {
@@ -592,231 +1275,198 @@ impl Runtime {
                     .collect::<Result<Vec<_>, _>>()?;
                 Ok(format!("{{{}}}", elements.join(", ")))
             }
+            Expression::Map(entries) => {
+                let elements: Vec<String> = entries
+                    .iter()
+                    .map(|(key, value)| {
+                        Ok(format!(
+                            "{}: {}",
+                            self.expression_to_string(key)?,
+                            self.expression_to_string(value)?
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, RuntimeError>>()?;
+                Ok(format!("map{{{}}}", elements.join(", ")))
+            }
             Expression::Variable(name) => {
-                let val = self
-                    .lookup_variable(name)
-                    .ok_or_else(|| RuntimeError::new(format!("Undefined variable '{}'", name)))?;
+                let val = self.require_variable(name)?;
                 self.expression_to_string(&val)
             }
-            Expression::FunctionCall { name, args } => {
-                let val = self.evaluate_expression(Expression::FunctionCall {
-                    name: name.clone(),
-                    args: args.clone(),
-                })?;
+            Expression::FunctionCall { .. } => {
+                let val = self.evaluate_expression(expr)?;
                 self.expression_to_string(&val)
             }
-            Expression::BinaryOperation { .. } | Expression::UnaryOperation { .. } => {
-                let val = self.evaluate_expression(expr.clone())?;
+            Expression::BinaryOperation { .. }
+            | Expression::UnaryOperation { .. }
+            | Expression::Conditional { .. } => {
+                let val = self.evaluate_expression(expr)?;
                 self.expression_to_string(&val)
             }
             _ => Ok("".to_string()),
         }
     }
 
-    fn evaluate_expression(&self, expr: Expression) -> Result<Expression, RuntimeError> {
+    fn evaluate_expression(&self, expr: &Expression) -> Result<Expression, RuntimeError> {
         match expr {
             Expression::Array(elements) => {
                 let evaluated_elements: Vec<Expression> = elements
-                    .into_iter()
+                    .iter()
                     .map(|e| self.evaluate_expression(e))
                     .collect::<Result<Vec<_>, _>>()?;
                 Ok(Expression::Array(evaluated_elements))
             }
             Expression::Object(properties) => {
-                let mut evaluated_properties: std::collections::HashMap<String, Expression> =
-                    std::collections::HashMap::new();
+                let mut evaluated_properties: ObjectMap = ObjectMap::new();
                 for (key, value) in properties {
-                    evaluated_properties.insert(key, self.evaluate_expression(value)?);
+                    evaluated_properties.insert(key.clone(), self.evaluate_expression(value)?);
                 }
                 Ok(Expression::Object(evaluated_properties))
             }
-            Expression::Number(n) => Ok(Expression::Number(n)),
-            Expression::Undefined => Ok(Expression::Undefined),
-            Expression::Null => Ok(Expression::Null),
-            Expression::Boolean(b) => Ok(Expression::Boolean(b)),
-            Expression::StringLiteral(s) => Ok(Expression::StringLiteral(s)),
-            Expression::StringInterpolation { parts } => Ok(Expression::StringLiteral(
-                self.render_interpolation(&parts)?,
-            )),
-            Expression::Variable(name) => self
-                .lookup_variable(&name)
-                .ok_or_else(|| RuntimeError::new(format!("Undefined variable '{}'", name))),
-            Expression::FunctionCall { name, args } => {
-                let evaluated_args: Vec<Expression> = args
-                    .into_iter()
-                    .map(|arg| self.evaluate_expression(arg))
-                    .collect::<Result<Vec<_>, _>>()?;
-
-                if name.contains('.') {
-                    let parts: Vec<&str> = name.split('.').collect();
-                    if parts.len() == 2 {
-                        let module_name = parts[0];
-                        let func_name = parts[1];
-                        let module_state = {
-                            let modules = self.modules.borrow();
-                            modules.get(module_name).cloned()
-                        };
-
-                        if let Some(module_state) = module_state {
-                            let definition =
-                                module_state.functions.borrow().get(func_name).cloned();
-                            if let Some(definition) = definition {
-                                if !definition.exported {
-                                    return Err(RuntimeError::new(format!(
-                                        "Function '{}.{}' is not exported",
-                                        module_name, func_name
-                                    )));
-                                }
-
-                                let local_vars = self.bind_arguments(
-                                    &definition.params,
-                                    &evaluated_args,
-                                    &name,
-                                )?;
-
-                                let mut nested_runtime = self.create_nested_runtime(
-                                    local_vars,
-                                    module_state.functions.clone(),
-                                    definition.scope.clone(),
-                                    definition.base_dir.clone(),
-                                );
-                                let value = nested_runtime.execute(&definition.body)?;
-                                return Ok(value.unwrap_or(Expression::Null));
-                            }
-                        }
-                    }
-                }
-
-                if let Some(native_func) = self.native_functions.get(&name) {
-                    return native_func(evaluated_args).ok_or_else(|| {
-                        RuntimeError::new(format!(
-                            "Native function '{}' failed for provided arguments",
-                            name
-                        ))
-                    });
-                }
-
-                let definition = self.functions.borrow().get(&name).cloned();
-                if let Some(definition) = definition {
-                    let local_vars =
-                        self.bind_arguments(&definition.params, &evaluated_args, &name)?;
-                    let mut nested_runtime = self.create_nested_runtime(
-                        local_vars,
-                        self.functions.clone(),
-                        definition.scope.clone(),
-                        definition.base_dir.clone(),
-                    );
-                    let value = nested_runtime.execute(&definition.body)?;
-                    return Ok(value.unwrap_or(Expression::Null));
-                }
-
-                Err(RuntimeError::new(format!("Unknown function '{}'", name)))
+            Expression::Number(_)
+            | Expression::Float(_)
+            | Expression::Undefined
+            | Expression::Null
+            | Expression::Boolean(_)
+            | Expression::StringLiteral(_)
+            | Expression::FrozenObject(_)
+            | Expression::Map(_) => Ok(expr.clone()),
+            Expression::StringInterpolation { parts } => {
+                Ok(Expression::StringLiteral(self.render_interpolation(parts)?))
             }
+            Expression::Variable(name) => self.require_variable(name),
+            Expression::FunctionCall { name, args } => self.call_named_function(name, args),
             Expression::Comparison {
                 left,
                 operator,
                 right,
             } => {
-                let left_val = self.evaluate_expression(*left)?;
-                let right_val = self.evaluate_expression(*right)?;
-
-                match (left_val, right_val) {
-                    (Expression::Number(l), Expression::Number(r)) => match operator.as_str() {
-                        "==" => Ok(Expression::Boolean(l == r)),
-                        "!=" => Ok(Expression::Boolean(l != r)),
-                        ">" => Ok(Expression::Boolean(l > r)),
-                        "<" => Ok(Expression::Boolean(l < r)),
-                        ">=" => Ok(Expression::Boolean(l >= r)),
-                        "<=" => Ok(Expression::Boolean(l <= r)),
-                        _ => Err(RuntimeError::new(format!(
-                            "Unsupported comparison operator '{}'",
-                            operator
-                        ))),
-                    },
-                    (Expression::Boolean(l), Expression::Boolean(r)) => match operator.as_str() {
-                        "==" => Ok(Expression::Boolean(l == r)),
-                        "!=" => Ok(Expression::Boolean(l != r)),
-                        _ => Err(RuntimeError::new(format!(
-                            "Unsupported comparison operator '{}' for booleans",
-                            operator
-                        ))),
-                    },
-                    (Expression::StringLiteral(l), Expression::StringLiteral(r)) => {
-                        match operator.as_str() {
-                            "==" => Ok(Expression::Boolean(l == r)),
-                            "!=" => Ok(Expression::Boolean(l != r)),
-                            _ => Err(RuntimeError::new(format!(
-                                "Unsupported comparison operator '{}' for strings",
-                                operator
-                            ))),
-                        }
-                    }
-                    _ => Err(RuntimeError::new(
-                        "Comparison operands must both be numbers, booleans, or strings",
-                    )),
-                }
+                let operator = ComparisonOperator::parse(operator)?;
+                let left_val = self.evaluate_expression(left)?;
+                let right_val = self.evaluate_expression(right)?;
+                operator.apply(left_val, right_val)
             }
             Expression::BinaryOperation {
                 left,
                 operator,
                 right,
             } => {
-                let left_val = self.evaluate_expression(*left)?;
-                let right_val = self.evaluate_expression(*right)?;
-
-                match (left_val, right_val) {
-                    (Expression::Number(l), Expression::Number(r)) => match operator.as_str() {
-                        "+" => Ok(Expression::Number(l + r)),
-                        "-" => Ok(Expression::Number(l - r)),
-                        "*" => Ok(Expression::Number(l * r)),
-                        "/" if r != 0 => Ok(Expression::Number(l / r)),
-                        "%" if r != 0 => Ok(Expression::Number(l % r)),
-                        "&" => Ok(Expression::Number(l & r)),
-                        "|" => Ok(Expression::Number(l | r)),
-                        "<<" if r >= 0 => Ok(Expression::Number(l.wrapping_shl(r as u32))),
-                        ">>" if r >= 0 => Ok(Expression::Number(l.wrapping_shr(r as u32))),
-                        "/" | "%" => Err(RuntimeError::new(
-                            "Division and modulo by zero are not allowed",
-                        )),
-                        "<<" | ">>" => Err(RuntimeError::new(
-                            "Shift count must be a non-negative number",
-                        )),
-                        _ => Err(RuntimeError::new(format!(
-                            "Unsupported binary operator '{}'",
-                            operator
-                        ))),
-                    },
-                    _ => Err(RuntimeError::new(format!(
-                        "Binary operator '{}' requires numeric operands",
-                        operator
-                    ))),
-                }
+                let operator = BinaryOperator::parse(operator)?;
+                let left_val = self.evaluate_expression(left)?;
+                let right_val = self.evaluate_expression(right)?;
+                operator.apply(left_val, right_val)
             }
             Expression::UnaryOperation { operator, expr } => {
-                let value = self.evaluate_expression(*expr)?;
-                match (operator.as_str(), value) {
-                    ("-", Expression::Number(number)) => Ok(Expression::Number(-number)),
-                    ("~", Expression::Number(number)) => Ok(Expression::Number(!number)),
-                    ("-", _) | ("~", _) => Err(RuntimeError::new(format!(
-                        "Unary operator '{}' requires a numeric operand",
-                        operator
-                    ))),
-                    _ => Err(RuntimeError::new(format!(
-                        "Unsupported unary operator '{}'",
-                        operator
-                    ))),
-                }
+                let operator = UnaryOperator::parse(operator)?;
+                operator.apply(self.evaluate_expression(expr)?)
             }
+            Expression::Conditional {
+                condition,
+                then_expr,
+                else_expr,
+            } => match self.evaluate_expression(condition)? {
+                Expression::Boolean(true) => self.evaluate_expression(then_expr),
+                Expression::Boolean(false) => self.evaluate_expression(else_expr),
+                _ => Err(RuntimeError::type_error(
+                    "conditional expression condition must evaluate to a boolean",
+                )),
+            },
             Expression::PropertyAccess { object, property } => {
-                self.resolve_property_access(*object, *property)
+                self.resolve_property_access(object, property)
+            }
+        }
+    }
+
+    fn call_named_function(
+        &self,
+        name: &str,
+        args: &[Expression],
+    ) -> Result<Expression, RuntimeError> {
+        let evaluated_args: Vec<Expression> = args
+            .iter()
+            .map(|arg| self.evaluate_expression(arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.call_with_arguments(name, evaluated_args)
+    }
+
+    fn record_call(&self, name: &str) {
+        if let Some(call_counts) = &self.call_counts {
+            *call_counts
+                .borrow_mut()
+                .entry(name.to_string())
+                .or_insert(0) += 1;
+        }
+    }
+
+    fn call_native(
+        name: &str,
+        function: NativeFunction,
+        args: Vec<Expression>,
+    ) -> Result<Expression, RuntimeError> {
+        function(args).ok_or_else(|| {
+            let message = match crate::stdlib::take_failure_reason() {
+                Some(reason) => format!("Native function '{}' failed: {}", name, reason),
+                None => format!("Native function '{}' failed for provided arguments", name),
+            };
+            RuntimeError::of_kind(ErrorKind::Native, message)
+        })
+    }
+
+    pub(super) fn call_with_arguments(
+        &self,
+        name: &str,
+        evaluated_args: Vec<Expression>,
+    ) -> Result<Expression, RuntimeError> {
+        self.record_call(name);
+
+        if name.contains('.') {
+            let parts: Vec<&str> = name.split('.').collect();
+            if parts.len() == 2 {
+                let module_name = parts[0];
+                let func_name = parts[1];
+                let module_state = {
+                    let modules = self.modules.borrow();
+                    modules.get(module_name).cloned()
+                };
+
+                if let Some(module_state) = module_state {
+                    let definition = module_state.functions.borrow().get(func_name).cloned();
+                    if let Some(definition) = definition {
+                        if !definition.exported {
+                            return Err(RuntimeError::new(format!(
+                                "Function '{}.{}' is not exported",
+                                module_name, func_name
+                            )));
+                        }
+
+                        return self.invoke_function(name, &definition, &evaluated_args);
+                    }
+                }
             }
         }
+
+        if let Some(result) = self.call_builtin(name, &evaluated_args) {
+            return result;
+        }
+
+        if let Some(native_func) = self.native_functions.get(name) {
+            return Self::call_native(name, *native_func, evaluated_args);
+        }
+
+        let definition = self.functions.borrow().get(name).cloned();
+        if let Some(definition) = definition {
+            return self.invoke_function(name, &definition, &evaluated_args);
+        }
+
+        Err(RuntimeError::of_kind(
+            ErrorKind::UndefinedFunction,
+            format!("Unknown function '{}'", name),
+        ))
     }
 
     fn assign_property(
         &mut self,
-        object: Expression,
-        property: Expression,
+        object: &Expression,
+        property: &Expression,
         value: Expression,
     ) -> Result<(), RuntimeError> {
         let (root_var, property_path) = self.collect_assignment_path(object, property)?;
@@ -826,19 +1476,22 @@ impl Runtime {
                 .variables
                 .borrow_mut()
                 .remove(&root_var)
-                .ok_or_else(|| RuntimeError::new(format!("Undefined variable '{}'", root_var)))?;
-            self.assign_property_path(&mut root_value, &property_path, value)?;
+                .ok_or_else(|| Self::undefined_variable(&root_var))?;
+            // Put the variable back before reporting a failed assignment so a
+            // caught error leaves it unchanged.
+            let result = self.assign_property_path(&mut root_value, &property_path, value);
             target_scope
                 .variables
                 .borrow_mut()
                 .insert(root_var, root_value);
+            result?;
         } else {
             if matches!(property_path.first(), Some(AccessKey::Number(_))) {
                 return Err(RuntimeError::new(
                     "Array assignment target must already exist",
                 ));
             }
-            let mut root_value = Expression::Object(std::collections::HashMap::new());
+            let mut root_value = Expression::Object(ObjectMap::new());
             self.assign_property_path(&mut root_value, &property_path, value)?;
             self.set_local_variable(root_var, root_value);
         }
@@ -848,9 +1501,9 @@ impl Runtime {
 
     fn collect_assignment_path(
         &self,
-        object: Expression,
-        property: Expression,
-    ) -> Result<(String, Vec<AccessKey>), RuntimeError> {
+        object: &Expression,
+        property: &Expression,
+    ) -> Result<(Name, Vec<AccessKey>), RuntimeError> {
         let mut segments = vec![self.evaluate_access_key(property)?];
         let mut current = object;
 
@@ -858,11 +1511,11 @@ impl Runtime {
             match current {
                 Expression::Variable(name) => {
                     segments.reverse();
-                    return Ok((name, segments));
+                    return Ok((name.clone(), segments));
                 }
                 Expression::PropertyAccess { object, property } => {
-                    segments.push(self.evaluate_access_key(*property)?);
-                    current = *object;
+                    segments.push(self.evaluate_access_key(property)?);
+                    current = object;
                 }
                 _ => {
                     return Err(RuntimeError::new(
@@ -886,6 +1539,9 @@ impl Runtime {
         }
 
         match (&path[0], expr) {
+            (_, Expression::FrozenObject(_)) => Err(RuntimeError::type_error(
+                "Cannot assign a property of a frozen object",
+            )),
             (AccessKey::String(key), Expression::Object(properties)) => {
                 if path.len() == 1 {
                     properties.insert(key.clone(), value);
@@ -894,9 +1550,12 @@ impl Runtime {
 
                 let entry = properties
                     .entry(key.clone())
-                    .or_insert_with(|| Expression::Object(std::collections::HashMap::new()));
+                    .or_insert_with(|| Expression::Object(ObjectMap::new()));
 
-                if !matches!(entry, Expression::Object(_) | Expression::Array(_)) {
+                if !matches!(
+                    entry,
+                    Expression::Object(_) | Expression::FrozenObject(_) | Expression::Array(_)
+                ) {
                     return Err(RuntimeError::new(format!(
                         "Cannot assign nested property through non-container value '{}'",
                         key
@@ -921,13 +1580,13 @@ impl Runtime {
 
                 self.assign_property_path(&mut elements[index], &path[1..], value)
             }
-            (AccessKey::String(_), Expression::Array(_)) => Err(RuntimeError::new(
+            (AccessKey::String(_), Expression::Array(_)) => Err(RuntimeError::type_error(
                 "Array assignment index must evaluate to a number",
             )),
-            (AccessKey::Number(_), Expression::Object(_)) => Err(RuntimeError::new(
+            (AccessKey::Number(_), Expression::Object(_)) => Err(RuntimeError::type_error(
                 "Object property key must evaluate to a string",
             )),
-            (_, _) => Err(RuntimeError::new(
+            (_, _) => Err(RuntimeError::type_error(
                 "Property assignment target must evaluate to an object or array",
             )),
         }
@@ -935,40 +1594,43 @@ impl Runtime {
 
     fn resolve_property_access(
         &self,
-        object: Expression,
-        property: Expression,
+        object: &Expression,
+        property: &Expression,
     ) -> Result<Expression, RuntimeError> {
         let property_key = self.evaluate_access_key(property)?;
 
-        if let Expression::Variable(module_name) = &object {
+        if let Expression::Variable(module_name) = object {
             if let AccessKey::String(property_name) = &property_key {
-                if let Some(module_state) = self.modules.borrow().get(module_name) {
-                    if let Some(definition) = module_state.functions.borrow().get(property_name) {
+                if let Some(module_state) = self.modules.borrow().get(&**module_name) {
+                    if let Some(definition) =
+                        module_state.functions.borrow().get(property_name.as_str())
+                    {
                         if !definition.exported {
                             return Err(RuntimeError::new(format!(
                                 "Function '{}.{}' is not exported",
                                 module_name, property_name
                             )));
                         }
-                        return Ok(Expression::Variable(format!(
-                            "{}::{}",
-                            module_name, property_name
-                        )));
+                        return Ok(Expression::Variable(
+                            format!("{}::{}", module_name, property_name).into(),
+                        ));
                     }
                 }
             }
         }
 
         match self.evaluate_expression(object)? {
-            Expression::Object(properties) => match property_key {
-                AccessKey::String(property_name) => Ok(properties
-                    .get(&property_name)
-                    .cloned()
-                    .unwrap_or(Expression::Undefined)),
-                AccessKey::Number(_) => Err(RuntimeError::new(
-                    "Object property key must evaluate to a string",
-                )),
-            },
+            Expression::Object(properties) | Expression::FrozenObject(properties) => {
+                match property_key {
+                    AccessKey::String(property_name) => Ok(properties
+                        .get(&property_name)
+                        .cloned()
+                        .unwrap_or(Expression::Undefined)),
+                    AccessKey::Number(_) => Err(RuntimeError::type_error(
+                        "Object property key must evaluate to a string",
+                    )),
+                }
+            }
             Expression::Array(elements) => match property_key {
                 AccessKey::Number(index) => {
                     if index < 0 {
@@ -979,30 +1641,33 @@ impl Runtime {
                         .cloned()
                         .unwrap_or(Expression::Undefined))
                 }
-                AccessKey::String(_) => {
-                    Err(RuntimeError::new("Array index must evaluate to a number"))
-                }
+                AccessKey::String(_) => Err(RuntimeError::type_error(
+                    "Array index must evaluate to a number",
+                )),
             },
-            _ => Err(RuntimeError::new(
+            _ => Err(RuntimeError::type_error(
                 "Property access target must evaluate to an object or array",
             )),
         }
     }
 
-    fn evaluate_access_key(&self, property: Expression) -> Result<AccessKey, RuntimeError> {
+    fn evaluate_access_key(&self, property: &Expression) -> Result<AccessKey, RuntimeError> {
         match self.evaluate_expression(property)? {
             Expression::StringLiteral(value) => Ok(AccessKey::String(value)),
             Expression::Number(value) => Ok(AccessKey::Number(value)),
             Expression::Undefined => Err(RuntimeError::new(
                 "Property key expression evaluated to undefined",
             )),
-            Expression::Null => Err(RuntimeError::new(
+            Expression::Null => Err(RuntimeError::type_error(
                 "Property key expression must evaluate to a string or number, found null",
             )),
-            Expression::Boolean(_) | Expression::Array(_) | Expression::Object(_) => Err(
-                RuntimeError::new("Property key expression must evaluate to a string or number"),
-            ),
-            other => Err(RuntimeError::new(format!(
+            Expression::Boolean(_)
+            | Expression::Array(_)
+            | Expression::Object(_)
+            | Expression::FrozenObject(_) => Err(RuntimeError::type_error(
+                "Property key expression must evaluate to a string or number",
+            )),
+            other => Err(RuntimeError::type_error(format!(
                 "Property key expression must evaluate to a string or number, found {}",
                 self.expression_to_string(&other)?
             ))),
@@ -1015,24 +1680,4 @@ impl Runtime {
         }
         Ok(index as usize)
     }
-
-    pub fn call_function(
-        &mut self,
-        name: &str,
-        args: Vec<Expression>,
-    ) -> Result<Option<Expression>, RuntimeError> {
-        let value = self.evaluate_expression(Expression::FunctionCall {
-            name: name.to_string(),
-            args,
-        })?;
-        Ok(Some(value))
-    }
-
-    pub fn get_variable(&self, name: &str) -> Option<Expression> {
-        self.lookup_variable(name)
-    }
-
-    pub fn has_function(&self, name: &str) -> bool {
-        self.functions.borrow().contains_key(name)
-    }
 }
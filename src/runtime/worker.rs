@@ -0,0 +1,247 @@
+//! Running user functions on other OS threads.
+//!
+//! A runtime shares its scopes and tables through `Rc`, so it cannot move to
+//! another thread. A `WorkerSnapshot` copies what a worker needs, the caller's
+//! function definitions and variable values plus those of the modules the
+//! functions were imported from, into plain data, and each worker thread
+//! rebuilds its own runtime from it. Workers see copies: assignments made
+//! inside a worker are not visible to the caller.
+//!
+//! `parallel_map` runs a scoped pool and waits for it. `thread_spawn` starts a
+//! detached thread whose handle is kept in `THREADS` until `thread_join` takes
+//! it back out.
+
+use super::runtime::{Runtime, RuntimeError};
+use crate::parser::ast::{Expression, Spanned, Statement};
+use crate::parser::symbol::Name;
+use rustc_hash::FxHashMap as HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+type ThreadHandle = JoinHandle<Result<Expression, RuntimeError>>;
+
+lazy_static::lazy_static! {
+    static ref THREADS: Mutex<HashMap<String, ThreadHandle>> = Mutex::new(HashMap::default());
+}
+
+static NEXT_THREAD_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// How often a timed `thread_join` checks whether the thread has finished.
+const JOIN_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+pub(super) struct FunctionSnapshot {
+    pub(super) params: Vec<Name>,
+    pub(super) body: Vec<Spanned<Statement>>,
+    pub(super) exported: bool,
+    /// Index into `WorkerSnapshot::modules` of the file that defined the
+    /// function; `None` once that file's runtime is gone.
+    pub(super) module: Option<usize>,
+}
+
+/// One file's function table, as names pointing into
+/// `WorkerSnapshot::definitions`, and the variables its functions can see.
+pub(super) struct ModuleSnapshot {
+    /// Path the module was loaded from, used as its module cache key.
+    pub(super) cache_key: Option<String>,
+    pub(super) functions: Vec<(Name, usize)>,
+    pub(super) variables: Vec<(Name, Expression)>,
+    pub(super) base_dir: PathBuf,
+}
+
+pub(super) struct WorkerSnapshot {
+    /// The caller's file first, then every module its functions came from.
+    pub(super) modules: Vec<ModuleSnapshot>,
+    pub(super) definitions: Vec<FunctionSnapshot>,
+}
+
+impl Runtime {
+    pub(super) fn parallel_map(&self, args: &[Expression]) -> Result<Expression, RuntimeError> {
+        let (items, function) = match args {
+            [Expression::Array(items), Expression::StringLiteral(function)] => (items, function),
+            _ => {
+                return Err(RuntimeError::new(
+                    "parallel_map expects an array and a function name",
+                ))
+            }
+        };
+
+        let snapshot = self.worker_snapshot();
+        run_parallel(&snapshot, function, items).map(Expression::Array)
+    }
+
+    pub(super) fn thread_spawn(&self, args: &[Expression]) -> Result<Expression, RuntimeError> {
+        let Some((Expression::StringLiteral(function), call_args)) = args.split_first() else {
+            return Err(RuntimeError::new(
+                "thread_spawn expects a function name followed by its arguments",
+            ));
+        };
+
+        let snapshot = self.worker_snapshot();
+        let function = function.clone();
+        let call_args = call_args.to_vec();
+        let handle = thread::spawn(move || {
+            Runtime::from_worker_snapshot(&snapshot).call_with_arguments(&function, call_args)
+        });
+
+        let id = format!("thread{}", NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed));
+        lock_threads().insert(id.clone(), handle);
+        Ok(Expression::StringLiteral(id))
+    }
+
+    pub(super) fn thread_join(&self, args: &[Expression]) -> Result<Expression, RuntimeError> {
+        let (id, timeout) = match args {
+            [Expression::StringLiteral(id)] => (id, None),
+            [Expression::StringLiteral(id), Expression::Number(millis)] if *millis >= 0 => {
+                (id, Some(Duration::from_millis(*millis as u64)))
+            }
+            _ => {
+                return Err(RuntimeError::new(
+                    "thread_join expects a thread id and an optional non-negative timeout in milliseconds",
+                ))
+            }
+        };
+
+        // A timed-out join returns null and leaves the thread registered, so it
+        // can be checked with thread_status or joined again later.
+        if let Some(timeout) = timeout {
+            if !wait_for_thread(id, timeout)? {
+                return Ok(Expression::Null);
+            }
+        }
+        join_thread(id).unwrap_or_else(|| Err(unknown_thread(id)))
+    }
+
+    pub(super) fn thread_status(&self, args: &[Expression]) -> Result<Expression, RuntimeError> {
+        let [Expression::StringLiteral(id)] = args else {
+            return Err(RuntimeError::new("thread_status expects a thread id"));
+        };
+
+        Ok(match lock_threads().get(id).map(JoinHandle::is_finished) {
+            Some(true) => Expression::StringLiteral("finished".to_string()),
+            Some(false) => Expression::StringLiteral("running".to_string()),
+            None => Expression::Null,
+        })
+    }
+
+    /// Joins every thread before reporting the first failure, so one failing
+    /// thread does not leave the others unjoined.
+    pub(super) fn thread_join_all(&self, args: &[Expression]) -> Result<Expression, RuntimeError> {
+        let [Expression::Array(ids)] = args else {
+            return Err(RuntimeError::new(
+                "thread_join_all expects an array of thread ids",
+            ));
+        };
+
+        let outcomes: Vec<Result<Expression, RuntimeError>> = ids
+            .iter()
+            .map(|id| match id {
+                Expression::StringLiteral(id) => join_thread(id).unwrap_or(Ok(Expression::Null)),
+                _ => Ok(Expression::Null),
+            })
+            .collect();
+        outcomes
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .map(Expression::Array)
+    }
+}
+
+/// Polls until the thread finishes, returning `false` once `timeout` passes.
+/// `JoinHandle` has no timed join, and the registry lock is only held for
+/// each check.
+fn wait_for_thread(id: &str, timeout: Duration) -> Result<bool, RuntimeError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let finished = lock_threads()
+            .get(id)
+            .map(JoinHandle::is_finished)
+            .ok_or_else(|| unknown_thread(id))?;
+        let now = Instant::now();
+        if finished {
+            return Ok(true);
+        }
+        if now >= deadline {
+            return Ok(false);
+        }
+        thread::sleep(JOIN_POLL_INTERVAL.min(deadline - now));
+    }
+}
+
+fn unknown_thread(id: &str) -> RuntimeError {
+    RuntimeError::new(format!("Unknown thread id '{}'", id))
+}
+
+/// Waits for the thread registered as `id`; `None` when no such thread exists.
+fn join_thread(id: &str) -> Option<Result<Expression, RuntimeError>> {
+    // Take the handle out before joining so the registry is not locked while
+    // this thread waits; other threads can keep spawning and joining.
+    let handle = lock_threads().remove(id)?;
+    Some(
+        handle
+            .join()
+            .unwrap_or_else(|_| Err(RuntimeError::new(format!("Thread '{}' panicked", id)))),
+    )
+}
+
+fn lock_threads() -> MutexGuard<'static, HashMap<String, ThreadHandle>> {
+    THREADS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Calls `function` once per item on a bounded pool of worker threads and
+/// returns the results in item order. The first failing item, by position,
+/// determines the error.
+fn run_parallel(
+    snapshot: &WorkerSnapshot,
+    function: &str,
+    items: &[Expression],
+) -> Result<Vec<Expression>, RuntimeError> {
+    let workers = thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(1)
+        .min(items.len());
+    let next_index = AtomicUsize::new(0);
+
+    let outcomes: Vec<Vec<(usize, Result<Expression, RuntimeError>)>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let runtime = Runtime::from_worker_snapshot(snapshot);
+                    let mut outcomes = Vec::new();
+                    loop {
+                        let index = next_index.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+                        let outcome = runtime.call_with_arguments(function, vec![item.clone()]);
+                        outcomes.push((index, outcome));
+                    }
+                    outcomes
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .map_err(|_| RuntimeError::new("parallel_map worker thread panicked"))
+            })
+            .collect::<Result<_, _>>()
+    })?;
+
+    let mut results: Vec<Option<Result<Expression, RuntimeError>>> =
+        (0..items.len()).map(|_| None).collect();
+    for (index, outcome) in outcomes.into_iter().flatten() {
+        results[index] = Some(outcome);
+    }
+    results
+        .into_iter()
+        .map(|outcome| outcome.expect("every index is claimed by exactly one worker"))
+        .collect()
+}
@@ -1,5 +1,7 @@
+use super::random::with_rng;
 use super::NativeFunctionEntry;
-use crate::parser::ast::Expression;
+use crate::parser::ast::{format_float, Expression};
+use rand::seq::SliceRandom;
 
 pub fn array_functions() -> Vec<NativeFunctionEntry> {
     vec![
@@ -8,10 +10,16 @@ pub fn array_functions() -> Vec<NativeFunctionEntry> {
         ("array_length", array_length),
         ("array_get", array_get),
         ("array_set", array_set),
+        ("array_insert", array_insert),
+        ("array_remove", array_remove),
+        ("array_first", array_first),
+        ("array_last", array_last),
+        ("array_rest", array_rest),
         ("array_slice", array_slice),
         ("array_join", array_join),
         ("array_to_string", array_to_string),
         ("array_range", array_range),
+        ("array_shuffle", array_shuffle),
     ]
 }
 
@@ -86,6 +94,76 @@ fn array_set(args: Vec<Expression>) -> Option<Expression> {
     }
 }
 
+fn array_insert(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 3 {
+        return None;
+    }
+    if let (Expression::Array(mut arr), Expression::Number(index), value) =
+        (args[0].clone(), &args[1], &args[2])
+    {
+        // Inserting at `len` appends.
+        let index = usize::try_from(*index).ok()?;
+        if index <= arr.len() {
+            arr.insert(index, value.clone());
+            Some(Expression::Array(arr))
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
+
+fn array_remove(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 2 {
+        return None;
+    }
+    if let (Expression::Array(mut arr), Expression::Number(index)) = (args[0].clone(), &args[1]) {
+        let index = usize::try_from(*index).ok()?;
+        if index < arr.len() {
+            arr.remove(index);
+            Some(Expression::Array(arr))
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
+
+fn array_first(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 1 {
+        return None;
+    }
+    if let Expression::Array(arr) = &args[0] {
+        Some(arr.first().cloned().unwrap_or(Expression::Null))
+    } else {
+        None
+    }
+}
+
+fn array_last(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 1 {
+        return None;
+    }
+    if let Expression::Array(arr) = &args[0] {
+        Some(arr.last().cloned().unwrap_or(Expression::Null))
+    } else {
+        None
+    }
+}
+
+fn array_rest(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 1 {
+        return None;
+    }
+    if let Expression::Array(arr) = &args[0] {
+        Some(Expression::Array(arr.iter().skip(1).cloned().collect()))
+    } else {
+        None
+    }
+}
+
 fn array_slice(args: Vec<Expression>) -> Option<Expression> {
     if args.len() != 3 {
         return None;
@@ -115,6 +193,7 @@ fn array_join(args: Vec<Expression>) -> Option<Expression> {
             .map(|expr| match expr {
                 Expression::StringLiteral(s) => s.clone(),
                 Expression::Number(n) => n.to_string(),
+                Expression::Float(n) => format_float(*n),
                 Expression::Boolean(b) => b.to_string(),
                 Expression::Null => "null".to_string(),
                 _ => String::new(),
@@ -136,21 +215,24 @@ fn array_to_string(args: Vec<Expression>) -> Option<Expression> {
             .iter()
             .map(|e| match e {
                 Expression::Number(n) => n.to_string(),
+                Expression::Float(n) => format_float(*n),
                 Expression::Boolean(b) => b.to_string(),
                 Expression::StringLiteral(s) => s.to_string(),
                 Expression::Undefined => "undefined".to_string(),
                 Expression::PropertyAccess { .. } => String::new(),
                 Expression::StringInterpolation { .. } => "<string interpolation>".to_string(),
-                Expression::Object(_) => String::new(),
+                Expression::Object(_) | Expression::FrozenObject(_) => String::new(),
+                Expression::Map(_) => "map{...}".to_string(),
                 Expression::Null => "null".to_string(),
                 Expression::Array(_) => "[...]".to_string(),
                 Expression::FunctionCall { name, args } => {
                     format!("{}({:?})", name, args)
                 }
-                Expression::Variable(name) => name.clone(),
+                Expression::Variable(name) => name.to_string(),
                 Expression::Comparison { .. } => String::new(),
                 Expression::BinaryOperation { .. } => String::new(),
                 Expression::UnaryOperation { .. } => String::new(),
+                Expression::Conditional { .. } => String::new(),
             })
             .collect::<Vec<String>>()
             .join("");
@@ -176,6 +258,19 @@ fn array_range(args: Vec<Expression>) -> Option<Expression> {
     }
 }
 
+fn array_shuffle(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 1 {
+        return None;
+    }
+    if let Expression::Array(mut arr) = args[0].clone() {
+        // SliceRandom::shuffle is a Fisher-Yates shuffle.
+        with_rng(|rng| arr.shuffle(rng));
+        Some(Expression::Array(arr))
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::array_functions;
@@ -220,6 +315,106 @@ mod tests {
         ));
     }
 
+    fn numbers(value: Option<Expression>) -> Vec<i32> {
+        match value {
+            Some(Expression::Array(items)) => items
+                .iter()
+                .map(|item| match item {
+                    Expression::Number(n) => *n,
+                    _ => panic!("unexpected element"),
+                })
+                .collect(),
+            _ => panic!("expected array"),
+        }
+    }
+
+    #[test]
+    fn insert_shifts_elements_right_at_start_middle_and_end() {
+        let insert = array_fn("array_insert");
+        let base = || Expression::Array(vec![Expression::Number(1), Expression::Number(2)]);
+
+        assert_eq!(
+            numbers(insert(vec![
+                base(),
+                Expression::Number(0),
+                Expression::Number(0)
+            ])),
+            vec![0, 1, 2]
+        );
+        assert_eq!(
+            numbers(insert(vec![
+                base(),
+                Expression::Number(1),
+                Expression::Number(9)
+            ])),
+            vec![1, 9, 2]
+        );
+        assert_eq!(
+            numbers(insert(vec![
+                base(),
+                Expression::Number(2),
+                Expression::Number(3)
+            ])),
+            vec![1, 2, 3]
+        );
+        assert!(insert(vec![base(), Expression::Number(3), Expression::Number(3)]).is_none());
+        assert!(insert(vec![base(), Expression::Number(-1), Expression::Number(3)]).is_none());
+    }
+
+    #[test]
+    fn remove_shifts_elements_left() {
+        let remove = array_fn("array_remove");
+        let base = Expression::Array(vec![
+            Expression::Number(1),
+            Expression::Number(2),
+            Expression::Number(3),
+        ]);
+
+        assert_eq!(
+            numbers(remove(vec![base.clone(), Expression::Number(1)])),
+            vec![1, 3]
+        );
+        assert!(remove(vec![base, Expression::Number(3)]).is_none());
+    }
+
+    #[test]
+    fn first_last_and_rest_handle_short_arrays() {
+        let first = array_fn("array_first");
+        let last = array_fn("array_last");
+        let rest = array_fn("array_rest");
+        let empty = Expression::Array(vec![]);
+        let single = Expression::Array(vec![Expression::Number(7)]);
+        let many = Expression::Array(vec![
+            Expression::Number(1),
+            Expression::Number(2),
+            Expression::Number(3),
+        ]);
+
+        assert!(matches!(first(vec![empty.clone()]), Some(Expression::Null)));
+        assert!(matches!(last(vec![empty.clone()]), Some(Expression::Null)));
+        assert!(numbers(rest(vec![empty])).is_empty());
+
+        assert!(matches!(
+            first(vec![single.clone()]),
+            Some(Expression::Number(7))
+        ));
+        assert!(matches!(
+            last(vec![single.clone()]),
+            Some(Expression::Number(7))
+        ));
+        assert!(numbers(rest(vec![single])).is_empty());
+
+        assert!(matches!(
+            first(vec![many.clone()]),
+            Some(Expression::Number(1))
+        ));
+        assert!(matches!(
+            last(vec![many.clone()]),
+            Some(Expression::Number(3))
+        ));
+        assert_eq!(numbers(rest(vec![many])), vec![2, 3]);
+    }
+
     #[test]
     fn range_slice_pop_and_join_work() {
         let range = array_fn("array_range");
@@ -242,4 +437,27 @@ mod tests {
             Some(Expression::Array(items)) if matches!(items.as_slice(), [Expression::Number(3)])
         ));
     }
+
+    #[test]
+    fn shuffle_keeps_length_and_elements() {
+        let shuffle = array_fn("array_shuffle");
+        let input: Vec<Expression> = (0..20).map(Expression::Number).collect();
+
+        let Some(Expression::Array(shuffled)) = shuffle(vec![Expression::Array(input.clone())])
+        else {
+            panic!("array_shuffle should return array");
+        };
+        assert_eq!(shuffled.len(), input.len());
+
+        let mut values: Vec<i32> = shuffled
+            .iter()
+            .map(|item| match item {
+                Expression::Number(n) => *n,
+                _ => panic!("unexpected element"),
+            })
+            .collect();
+        values.sort();
+        assert_eq!(values, (0..20).collect::<Vec<i32>>());
+        assert!(shuffle(vec![Expression::Number(1)]).is_none());
+    }
 }
@@ -0,0 +1,126 @@
+use super::NativeFunctionEntry;
+use crate::parser::ast::Expression;
+
+pub fn bit_functions() -> Vec<NativeFunctionEntry> {
+    vec![
+        ("bit_and", bit_and),
+        ("bit_or", bit_or),
+        ("bit_xor", bit_xor),
+        ("bit_not", bit_not),
+        ("bit_shift_left", bit_shift_left),
+        ("bit_shift_right", bit_shift_right),
+    ]
+}
+
+fn number_pair(args: &[Expression]) -> Option<(i32, i32)> {
+    if args.len() != 2 {
+        return None;
+    }
+    match (&args[0], &args[1]) {
+        (Expression::Number(a), Expression::Number(b)) => Some((*a, *b)),
+        _ => None,
+    }
+}
+
+fn bit_and(args: Vec<Expression>) -> Option<Expression> {
+    let (a, b) = number_pair(&args)?;
+    Some(Expression::Number(a & b))
+}
+
+fn bit_or(args: Vec<Expression>) -> Option<Expression> {
+    let (a, b) = number_pair(&args)?;
+    Some(Expression::Number(a | b))
+}
+
+fn bit_xor(args: Vec<Expression>) -> Option<Expression> {
+    let (a, b) = number_pair(&args)?;
+    Some(Expression::Number(a ^ b))
+}
+
+fn bit_not(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 1 {
+        return None;
+    }
+    if let Expression::Number(a) = &args[0] {
+        Some(Expression::Number(!a))
+    } else {
+        None
+    }
+}
+
+fn bit_shift_left(args: Vec<Expression>) -> Option<Expression> {
+    let (value, shift) = number_pair(&args)?;
+    if shift < 0 {
+        return None;
+    }
+    Some(Expression::Number(value.wrapping_shl(shift as u32)))
+}
+
+fn bit_shift_right(args: Vec<Expression>) -> Option<Expression> {
+    let (value, shift) = number_pair(&args)?;
+    if shift < 0 {
+        return None;
+    }
+    Some(Expression::Number(value.wrapping_shr(shift as u32)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bit_functions;
+    use crate::parser::ast::Expression;
+
+    fn bit_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
+        bit_functions()
+            .into_iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, f)| f)
+            .expect("missing bit function")
+    }
+
+    #[test]
+    fn and_or_xor_return_expected_values() {
+        let and = bit_fn("bit_and");
+        let or = bit_fn("bit_or");
+        let xor = bit_fn("bit_xor");
+
+        assert!(matches!(
+            and(vec![Expression::Number(6), Expression::Number(3)]),
+            Some(Expression::Number(2))
+        ));
+        assert!(matches!(
+            or(vec![Expression::Number(4), Expression::Number(1)]),
+            Some(Expression::Number(5))
+        ));
+        assert!(matches!(
+            xor(vec![Expression::Number(6), Expression::Number(3)]),
+            Some(Expression::Number(5))
+        ));
+    }
+
+    #[test]
+    fn not_inverts_all_bits() {
+        let not = bit_fn("bit_not");
+        assert!(matches!(
+            not(vec![Expression::Number(1)]),
+            Some(Expression::Number(-2))
+        ));
+        assert!(not(vec![Expression::StringLiteral("1".to_string())]).is_none());
+    }
+
+    #[test]
+    fn shifts_return_expected_values_and_reject_negative_counts() {
+        let shift_left = bit_fn("bit_shift_left");
+        let shift_right = bit_fn("bit_shift_right");
+
+        assert!(matches!(
+            shift_left(vec![Expression::Number(1), Expression::Number(4)]),
+            Some(Expression::Number(16))
+        ));
+        assert!(matches!(
+            shift_right(vec![Expression::Number(16), Expression::Number(2)]),
+            Some(Expression::Number(4))
+        ));
+        assert!(shift_left(vec![Expression::Number(1), Expression::Number(-1)]).is_none());
+        assert!(shift_right(vec![Expression::Number(1), Expression::Number(-1)]).is_none());
+    }
+}
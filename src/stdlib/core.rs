@@ -1,7 +1,8 @@
 use super::NativeFunctionEntry;
 use crate::parser::ast::Expression;
+use std::io::Write;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub fn core_functions() -> Vec<NativeFunctionEntry> {
     vec![
@@ -18,14 +19,61 @@ pub fn core_functions() -> Vec<NativeFunctionEntry> {
                 None
             }
         }),
+        ("sleep_ms", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                match &args[0] {
+                    Expression::Number(millis) if *millis >= 0 => {
+                        thread::sleep(Duration::from_millis(*millis as u64));
+                        Some(Expression::Null)
+                    }
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        }),
+        ("sleep_until", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                // Epoch milliseconds overflow i32, so floats are accepted too.
+                let target = match &args[0] {
+                    Expression::Number(millis) => f64::from(*millis),
+                    Expression::Float(millis) if millis.is_finite() => *millis,
+                    _ => return None,
+                };
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+                let remaining = target - now.as_millis() as f64;
+                if remaining > 0.0 {
+                    thread::sleep(Duration::from_millis(remaining as u64));
+                }
+                Some(Expression::Null)
+            } else {
+                None
+            }
+        }),
+        ("process_exit", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                match &args[0] {
+                    Expression::Number(code) => {
+                        let _ = std::io::stdout().flush();
+                        let _ = std::io::stderr().flush();
+                        // Exit statuses are a single byte on Unix.
+                        std::process::exit((*code).clamp(0, 255));
+                    }
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        }),
         ("type_of", |args: Vec<Expression>| {
             if args.len() == 1 {
                 let type_name = match &args[0] {
                     Expression::StringLiteral(_) => "string",
-                    Expression::Number(_) => "number",
+                    Expression::Number(_) | Expression::Float(_) => "number",
                     Expression::Boolean(_) => "boolean",
                     Expression::Array(_) => "array",
-                    Expression::Object(_) => "object",
+                    Expression::Object(_) | Expression::FrozenObject(_) => "object",
+                    Expression::Map(_) => "map",
                     Expression::Undefined => "undefined",
                     Expression::Null => "null",
                     Expression::FunctionCall { .. } => "function_call",
@@ -34,6 +82,7 @@ pub fn core_functions() -> Vec<NativeFunctionEntry> {
                     Expression::Comparison { .. } => "comparison",
                     Expression::BinaryOperation { .. } => "binary_operation",
                     Expression::UnaryOperation { .. } => "unary_operation",
+                    Expression::Conditional { .. } => "conditional",
                     Expression::StringInterpolation { .. } => "string",
                 };
                 Some(Expression::StringLiteral(type_name.to_string()))
@@ -51,6 +100,56 @@ pub fn core_functions() -> Vec<NativeFunctionEntry> {
                 None
             }
         }),
+        ("is_number", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                Some(Expression::Boolean(matches!(
+                    &args[0],
+                    Expression::Number(_) | Expression::Float(_)
+                )))
+            } else {
+                None
+            }
+        }),
+        ("is_string", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                Some(Expression::Boolean(matches!(
+                    &args[0],
+                    Expression::StringLiteral(_)
+                )))
+            } else {
+                None
+            }
+        }),
+        ("is_array", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                Some(Expression::Boolean(matches!(
+                    &args[0],
+                    Expression::Array(_)
+                )))
+            } else {
+                None
+            }
+        }),
+        ("is_object", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                Some(Expression::Boolean(matches!(
+                    &args[0],
+                    Expression::Object(_) | Expression::FrozenObject(_)
+                )))
+            } else {
+                None
+            }
+        }),
+        ("is_boolean", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                Some(Expression::Boolean(matches!(
+                    &args[0],
+                    Expression::Boolean(_)
+                )))
+            } else {
+                None
+            }
+        }),
         ("exec", |args: Vec<Expression>| {
             if args.len() == 1 {
                 match &args[0] {
@@ -75,7 +174,8 @@ pub fn core_functions() -> Vec<NativeFunctionEntry> {
 #[cfg(test)]
 mod tests {
     use super::core_functions;
-    use crate::parser::ast::Expression;
+    use crate::parser::ast::{Expression, ObjectMap};
+    use std::time::{Duration, Instant};
 
     #[test]
     fn exec_returns_none_for_missing_command_instead_of_panicking() {
@@ -90,4 +190,87 @@ mod tests {
         )]);
         assert!(result.is_none());
     }
+
+    fn core_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
+        core_functions()
+            .into_iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, f)| f)
+            .expect("missing core function")
+    }
+
+    fn check(name: &str, value: Expression) -> bool {
+        match core_fn(name)(vec![value]) {
+            Some(Expression::Boolean(result)) => result,
+            other => panic!("{} should return a boolean, got {:?}", name, other),
+        }
+    }
+
+    #[test]
+    fn sleep_ms_waits_roughly_the_requested_time() {
+        let sleep_ms = core_fn("sleep_ms");
+
+        let started = Instant::now();
+        assert!(matches!(
+            sleep_ms(vec![Expression::Number(10)]),
+            Some(Expression::Null)
+        ));
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(10));
+        assert!(elapsed < Duration::from_millis(500));
+        assert!(sleep_ms(vec![Expression::Number(-1)]).is_none());
+    }
+
+    #[test]
+    fn sleep_until_returns_immediately_for_past_times() {
+        let sleep_until = core_fn("sleep_until");
+
+        let started = Instant::now();
+        assert!(matches!(
+            sleep_until(vec![Expression::Float(1_000_000.0)]),
+            Some(Expression::Null)
+        ));
+        assert!(matches!(
+            sleep_until(vec![Expression::Number(0)]),
+            Some(Expression::Null)
+        ));
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
+
+    #[test]
+    fn is_number_matches_integers_and_floats() {
+        assert!(check("is_number", Expression::Number(1)));
+        assert!(check("is_number", Expression::Float(1.5)));
+        assert!(!check(
+            "is_number",
+            Expression::StringLiteral("1".to_string())
+        ));
+    }
+
+    #[test]
+    fn is_string_matches_only_strings() {
+        assert!(check(
+            "is_string",
+            Expression::StringLiteral("a".to_string())
+        ));
+        assert!(!check("is_string", Expression::Number(1)));
+    }
+
+    #[test]
+    fn is_array_matches_only_arrays() {
+        assert!(check("is_array", Expression::Array(vec![])));
+        assert!(!check("is_array", Expression::Null));
+    }
+
+    #[test]
+    fn is_object_matches_only_objects() {
+        assert!(check("is_object", Expression::Object(ObjectMap::new())));
+        assert!(!check("is_object", Expression::Array(vec![])));
+    }
+
+    #[test]
+    fn is_boolean_matches_only_booleans() {
+        assert!(check("is_boolean", Expression::Boolean(false)));
+        assert!(!check("is_boolean", Expression::Number(0)));
+    }
 }
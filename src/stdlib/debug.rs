@@ -1,5 +1,74 @@
-use super::NativeFunctionEntry;
-use crate::parser::ast::Expression;
+use super::{as_float, NativeFunctionEntry};
+use crate::parser::ast::{format_float, Expression};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static ASSERTION_PASSES: AtomicUsize = AtomicUsize::new(0);
+static ASSERTION_FAILURES: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of failed assertions recorded by the assertion helpers so far.
+pub fn assertion_failures() -> usize {
+    ASSERTION_FAILURES.load(Ordering::Relaxed)
+}
+
+/// The `Assertions: N passed, M failed` line printed when a run ends, or `None`
+/// if no assertion helper was called.
+pub fn assertion_summary() -> Option<String> {
+    let passed = ASSERTION_PASSES.load(Ordering::Relaxed);
+    let failed = assertion_failures();
+    if passed + failed == 0 {
+        return None;
+    }
+    Some(format!("Assertions: {} passed, {} failed", passed, failed))
+}
+
+/// Records the outcome of an assertion and returns it as the helper's result.
+fn record_assertion(passed: bool) -> Option<Expression> {
+    let counter = if passed {
+        &ASSERTION_PASSES
+    } else {
+        &ASSERTION_FAILURES
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+    Some(Expression::Boolean(passed))
+}
+
+/// Renders a value the way the runtime prints nested values, e.g. `{a: 1}`.
+fn render(expr: &Expression) -> String {
+    match expr {
+        Expression::Number(n) => n.to_string(),
+        Expression::Float(n) => format_float(*n),
+        Expression::Boolean(b) => b.to_string(),
+        Expression::StringLiteral(s) => format!("\"{}\"", s),
+        Expression::Undefined => "undefined".to_string(),
+        Expression::Null => "null".to_string(),
+        Expression::Array(items) => {
+            let elements: Vec<String> = items.iter().map(render).collect();
+            format!("[{}]", elements.join(", "))
+        }
+        Expression::Object(properties) | Expression::FrozenObject(properties) => {
+            let elements: Vec<String> = properties
+                .iter()
+                .map(|(key, value)| format!("{}: {}", key, render(value)))
+                .collect();
+            format!("{{{}}}", elements.join(", "))
+        }
+        Expression::Map(entries) => {
+            let elements: Vec<String> = entries
+                .iter()
+                .map(|(key, value)| format!("{}: {}", render(key), render(value)))
+                .collect();
+            format!("map{{{}}}", elements.join(", "))
+        }
+        Expression::Variable(name) => format!("<variable {}>", name),
+        Expression::FunctionCall { name, .. } => format!("<call {}>", name),
+        Expression::Comparison { .. } => "<comparison>".to_string(),
+        Expression::BinaryOperation { .. } => "<binary operation>".to_string(),
+        Expression::UnaryOperation { .. } => "<unary operation>".to_string(),
+        Expression::Conditional { .. } => "<conditional>".to_string(),
+        Expression::PropertyAccess { .. } => "<property access>".to_string(),
+        Expression::StringInterpolation { .. } => "<string interpolation>".to_string(),
+    }
+}
 
 pub fn debug_functions() -> Vec<NativeFunctionEntry> {
     vec![
@@ -13,32 +82,73 @@ pub fn debug_functions() -> Vec<NativeFunctionEntry> {
             }
         }),
         ("dump", |args: Vec<Expression>| {
-            if let Some(value) = args.first() {
-                println!("{:?}", value);
-            }
-            None
+            let value = args.first()?;
+            println!("{}", render(value));
+            Some(Expression::Null)
         }),
         ("assert_equal", |args: Vec<Expression>| {
-            if args.len() == 2 {
-                let result = match (&args[0], &args[1]) {
-                    (Expression::Number(a), Expression::Number(b)) => a == b,
-                    (Expression::Boolean(a), Expression::Boolean(b)) => a == b,
-                    (Expression::StringLiteral(a), Expression::StringLiteral(b)) => a == b,
-                    (Expression::Null, Expression::Null) => true,
-                    _ => false,
-                };
+            let (left, right, message) = match args.as_slice() {
+                [left, right] => (left, right, None),
+                [left, right, Expression::StringLiteral(message)] => (left, right, Some(message)),
+                _ => return None,
+            };
+            // Numbers compare by value across integers and floats, like `==`.
+            let result = match (left, right) {
+                (Expression::Boolean(a), Expression::Boolean(b)) => a == b,
+                (Expression::StringLiteral(a), Expression::StringLiteral(b)) => a == b,
+                (Expression::Null, Expression::Null) => true,
+                _ => matches!((as_float(left), as_float(right)), (Some(a), Some(b)) if a == b),
+            };
+            if !result {
+                match message {
+                    Some(message) => println!(
+                        "Assertion failed: {} ({} != {})",
+                        message,
+                        render(left),
+                        render(right)
+                    ),
+                    None => println!("Assertion failed: {} != {}", render(left), render(right)),
+                }
+            }
+            record_assertion(result)
+        }),
+        ("assert_near", |args: Vec<Expression>| {
+            if args.len() == 3 {
+                let a = as_float(&args[0])?;
+                let b = as_float(&args[1])?;
+                let epsilon = as_float(&args[2]).filter(|epsilon| *epsilon >= 0.0)?;
+                let result = (a - b).abs() <= epsilon;
+                if !result {
+                    println!(
+                        "Assertion failed: {} is not within {} of {}",
+                        render(&args[0]),
+                        render(&args[2]),
+                        render(&args[1])
+                    );
+                }
+                record_assertion(result)
+            } else {
+                None
+            }
+        }),
+        ("assert_none", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                let result = matches!(args[0], Expression::Null | Expression::Undefined);
                 if !result {
-                    println!("Assertion failed: {:?} != {:?}", args[0], args[1]);
+                    println!("Assertion failed: expected null, got {}", render(&args[0]));
                 }
+                record_assertion(result)
+            } else {
+                None
             }
-            None
         }),
     ]
 }
 
 #[cfg(test)]
 mod tests {
-    use super::debug_functions;
+    use super::{assertion_failures, assertion_summary, debug_functions, render};
+    use crate::parser::ast::{Expression, ObjectMap};
 
     #[test]
     fn dump_with_no_arguments_does_not_panic() {
@@ -51,4 +161,136 @@ mod tests {
         let result = dump(vec![]);
         assert!(result.is_none());
     }
+
+    fn debug_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
+        debug_functions()
+            .into_iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, f)| f)
+            .expect("missing debug function")
+    }
+
+    #[test]
+    fn assert_none_passes_for_null_and_records_failure_for_value() {
+        let assert_none = debug_fn("assert_none");
+
+        let before = assertion_failures();
+        assert!(matches!(
+            assert_none(vec![Expression::Null]),
+            Some(Expression::Boolean(true))
+        ));
+        assert!(matches!(
+            assert_none(vec![Expression::Number(1)]),
+            Some(Expression::Boolean(false))
+        ));
+        assert!(assertion_failures() > before);
+    }
+
+    #[test]
+    fn assert_near_compares_within_epsilon() {
+        let assert_near = debug_fn("assert_near");
+
+        let before = assertion_failures();
+        assert!(matches!(
+            assert_near(vec![
+                Expression::Float(0.1 + 0.2),
+                Expression::Float(0.3),
+                Expression::Float(1e-9)
+            ]),
+            Some(Expression::Boolean(true))
+        ));
+        assert!(matches!(
+            assert_near(vec![
+                Expression::Number(3),
+                Expression::Float(3.5),
+                Expression::Float(0.1)
+            ]),
+            Some(Expression::Boolean(false))
+        ));
+        assert!(assertion_failures() > before);
+        assert!(assert_near(vec![
+            Expression::Float(1.0),
+            Expression::Float(1.0),
+            Expression::Float(-1.0)
+        ])
+        .is_none());
+    }
+
+    #[test]
+    fn assert_equal_accepts_a_custom_message_and_counts_outcomes() {
+        let assert_equal = debug_fn("assert_equal");
+
+        let before = assertion_failures();
+        assert!(matches!(
+            assert_equal(vec![Expression::Number(2), Expression::Number(2)]),
+            Some(Expression::Boolean(true))
+        ));
+        assert!(matches!(
+            assert_equal(vec![
+                Expression::Number(2),
+                Expression::Number(3),
+                Expression::StringLiteral("totals differ".to_string())
+            ]),
+            Some(Expression::Boolean(false))
+        ));
+        assert!(assertion_failures() > before);
+        assert!(assertion_summary().is_some());
+        assert!(assert_equal(vec![
+            Expression::Number(2),
+            Expression::Number(2),
+            Expression::Number(3)
+        ])
+        .is_none());
+    }
+
+    #[test]
+    fn assert_equal_compares_integers_and_floats_by_value() {
+        let assert_equal = debug_fn("assert_equal");
+
+        assert!(matches!(
+            assert_equal(vec![Expression::Float(1.5), Expression::Float(1.5)]),
+            Some(Expression::Boolean(true))
+        ));
+        assert!(matches!(
+            assert_equal(vec![Expression::Number(3), Expression::Float(3.0)]),
+            Some(Expression::Boolean(true))
+        ));
+        assert!(matches!(
+            assert_equal(vec![Expression::Float(1.5), Expression::Number(2)]),
+            Some(Expression::Boolean(false))
+        ));
+        assert!(matches!(
+            assert_equal(vec![
+                Expression::Number(3),
+                Expression::StringLiteral("3".to_string())
+            ]),
+            Some(Expression::Boolean(false))
+        ));
+    }
+
+    #[test]
+    fn render_prints_nested_objects_without_enum_names() {
+        let mut inner = ObjectMap::new();
+        inner.insert("b".to_string(), Expression::StringLiteral("x".to_string()));
+        inner.insert("a".to_string(), Expression::Float(1.5));
+        let mut outer = ObjectMap::new();
+        outer.insert("z".to_string(), Expression::Null);
+        outer.insert("inner".to_string(), Expression::Object(inner));
+        outer.insert(
+            "items".to_string(),
+            Expression::Array(vec![Expression::Number(1), Expression::Boolean(true)]),
+        );
+
+        assert_eq!(
+            render(&Expression::Object(outer)),
+            "{z: null, inner: {b: \"x\", a: 1.5}, items: [1, true]}"
+        );
+        assert_eq!(
+            render(&Expression::Array(vec![
+                Expression::Float(2.0),
+                Expression::Number(2)
+            ])),
+            "[2.0, 2]"
+        );
+    }
 }
@@ -1,14 +1,43 @@
-use super::NativeFunctionEntry;
+use super::{fail, NativeFunctionEntry};
 use crate::parser::ast::Expression;
+use std::borrow::Cow;
 use std::fs;
 use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 pub fn fs_functions() -> Vec<NativeFunctionEntry> {
     vec![
         ("read_file", |args: Vec<Expression>| {
             if args.len() == 1 {
                 if let Expression::StringLiteral(path) = &args[0] {
-                    fs::read_to_string(path).ok().map(Expression::StringLiteral)
+                    match fs::read_to_string(path) {
+                        Ok(content) => Some(Expression::StringLiteral(content)),
+                        Err(error) => fail(format!("cannot read '{}': {}", path, error)),
+                    }
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
+        ("read_lines", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                if let Expression::StringLiteral(path) = &args[0] {
+                    // str::lines strips both "\n" and "\r\n" endings.
+                    match fs::read_to_string(path) {
+                        Ok(content) => Some(Expression::Array(
+                            content
+                                .lines()
+                                .map(|line| Expression::StringLiteral(line.to_string()))
+                                .collect(),
+                        )),
+                        Err(error) => fail(format!("cannot read '{}': {}", path, error)),
+                    }
                 } else {
                     None
                 }
@@ -21,12 +50,11 @@ pub fn fs_functions() -> Vec<NativeFunctionEntry> {
                 if let (Expression::StringLiteral(path), Expression::StringLiteral(content)) =
                     (&args[0], &args[1])
                 {
-                    match fs::File::create(path) {
-                        Ok(mut file) => match file.write_all(content.as_bytes()) {
-                            Ok(_) => Some(Expression::Null),
-                            Err(_) => None,
-                        },
-                        Err(_) => None,
+                    match fs::File::create(path)
+                        .and_then(|mut file| file.write_all(content.as_bytes()))
+                    {
+                        Ok(_) => Some(Expression::Null),
+                        Err(error) => fail(format!("cannot write '{}': {}", path, error)),
                     }
                 } else {
                     None
@@ -40,12 +68,14 @@ pub fn fs_functions() -> Vec<NativeFunctionEntry> {
                 if let (Expression::StringLiteral(path), Expression::StringLiteral(content)) =
                     (&args[0], &args[1])
                 {
-                    match fs::OpenOptions::new().append(true).create(true).open(path) {
-                        Ok(mut file) => match file.write_all(content.as_bytes()) {
-                            Ok(_) => Some(Expression::Null),
-                            Err(_) => None,
-                        },
-                        Err(_) => None,
+                    match fs::OpenOptions::new()
+                        .append(true)
+                        .create(true)
+                        .open(path)
+                        .and_then(|mut file| file.write_all(content.as_bytes()))
+                    {
+                        Ok(_) => Some(Expression::Null),
+                        Err(error) => fail(format!("cannot append to '{}': {}", path, error)),
                     }
                 } else {
                     None
@@ -70,7 +100,7 @@ pub fn fs_functions() -> Vec<NativeFunctionEntry> {
                 if let Expression::StringLiteral(path) = &args[0] {
                     match fs::remove_file(path) {
                         Ok(_) => Some(Expression::Null),
-                        Err(_) => None,
+                        Err(error) => fail(format!("cannot delete '{}': {}", path, error)),
                     }
                 } else {
                     None
@@ -86,7 +116,27 @@ pub fn fs_functions() -> Vec<NativeFunctionEntry> {
                 {
                     match fs::rename(from, to) {
                         Ok(_) => Some(Expression::Null),
-                        Err(_) => None,
+                        Err(error) => {
+                            fail(format!("cannot rename '{}' to '{}': {}", from, to, error))
+                        }
+                    }
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
+        ("copy_file", |args: Vec<Expression>| {
+            if args.len() == 2 {
+                if let (Expression::StringLiteral(from), Expression::StringLiteral(to)) =
+                    (&args[0], &args[1])
+                {
+                    match fs::copy(from, to) {
+                        Ok(_) => Some(Expression::Null),
+                        Err(error) => {
+                            fail(format!("cannot copy '{}' to '{}': {}", from, to, error))
+                        }
                     }
                 } else {
                     None
@@ -100,7 +150,41 @@ pub fn fs_functions() -> Vec<NativeFunctionEntry> {
                 if let Expression::StringLiteral(path) = &args[0] {
                     match fs::create_dir_all(path) {
                         Ok(_) => Some(Expression::Null),
-                        Err(_) => None,
+                        Err(error) => {
+                            fail(format!("cannot create directory '{}': {}", path, error))
+                        }
+                    }
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
+        ("delete_dir", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                if let Expression::StringLiteral(path) = &args[0] {
+                    match fs::remove_dir(path) {
+                        Ok(_) => Some(Expression::Null),
+                        Err(error) => {
+                            fail(format!("cannot delete directory '{}': {}", path, error))
+                        }
+                    }
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
+        ("delete_dir_recursive", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                if let Expression::StringLiteral(path) = &args[0] {
+                    match fs::remove_dir_all(path) {
+                        Ok(_) => Some(Expression::Null),
+                        Err(error) => {
+                            fail(format!("cannot delete directory '{}': {}", path, error))
+                        }
                     }
                 } else {
                     None
@@ -122,7 +206,7 @@ pub fn fs_functions() -> Vec<NativeFunctionEntry> {
                             }
                             Some(Expression::Array(names))
                         }
-                        Err(_) => None,
+                        Err(error) => fail(format!("cannot list '{}': {}", path, error)),
                     }
                 } else {
                     None
@@ -131,13 +215,188 @@ pub fn fs_functions() -> Vec<NativeFunctionEntry> {
                 None
             }
         }),
+        ("file_size", |args: Vec<Expression>| {
+            path_metadata(args, |metadata| {
+                // Sizes beyond i32 fall back to a float rather than wrapping.
+                let size = metadata.len();
+                Some(match i32::try_from(size) {
+                    Ok(size) => Expression::Number(size),
+                    Err(_) => Expression::Float(size as f64),
+                })
+            })
+        }),
+        ("file_modified", |args: Vec<Expression>| {
+            path_metadata(args, |metadata| {
+                let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+                Some(Expression::Float(modified.as_millis() as f64))
+            })
+        }),
+        ("is_directory", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                if let Expression::StringLiteral(path) = &args[0] {
+                    Some(Expression::Boolean(Path::new(path).is_dir()))
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
+        ("temp_dir", |args: Vec<Expression>| {
+            if args.is_empty() {
+                Some(Expression::StringLiteral(
+                    std::env::temp_dir().to_string_lossy().to_string(),
+                ))
+            } else {
+                None
+            }
+        }),
+        ("temp_file", |args: Vec<Expression>| {
+            if args.is_empty() {
+                Some(Expression::StringLiteral(
+                    unique_temp_path().to_string_lossy().to_string(),
+                ))
+            } else {
+                None
+            }
+        }),
+        ("path_join", |args: Vec<Expression>| {
+            if args.is_empty() {
+                return None;
+            }
+            let mut path = PathBuf::new();
+            for segment in &args {
+                if let Expression::StringLiteral(segment) = segment {
+                    path.push(segment);
+                } else {
+                    return None;
+                }
+            }
+            Some(Expression::StringLiteral(
+                path.to_string_lossy().to_string(),
+            ))
+        }),
+        ("path_normalize", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                if let Expression::StringLiteral(path) = &args[0] {
+                    let normalized = normalize(Path::new(path));
+                    Some(Expression::StringLiteral(
+                        normalized.to_string_lossy().to_string(),
+                    ))
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
+        ("path_basename", |args: Vec<Expression>| {
+            path_component(args, |path| {
+                path.file_name().map(|name| name.to_string_lossy())
+            })
+        }),
+        ("path_dirname", |args: Vec<Expression>| {
+            path_component(args, |path| {
+                path.parent()
+                    .filter(|parent| !parent.as_os_str().is_empty())
+                    .map(|parent| parent.to_string_lossy())
+            })
+        }),
+        ("path_extension", |args: Vec<Expression>| {
+            path_component(args, |path| {
+                path.extension().map(|ext| ext.to_string_lossy())
+            })
+        }),
     ]
 }
 
+/// Picks a path in the system temp directory that does not exist yet. The file
+/// itself is not created.
+fn unique_temp_path() -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    loop {
+        let counter = TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!(
+            "vexel_{}_{}_{}.tmp",
+            std::process::id(),
+            nanos,
+            counter
+        ));
+        if !path.exists() {
+            return path;
+        }
+    }
+}
+
+/// Reads metadata for a single path argument; missing paths yield `null`.
+fn path_metadata<F>(args: Vec<Expression>, read: F) -> Option<Expression>
+where
+    F: Fn(&fs::Metadata) -> Option<Expression>,
+{
+    if args.len() != 1 {
+        return None;
+    }
+    if let Expression::StringLiteral(path) = &args[0] {
+        match fs::metadata(path) {
+            Ok(metadata) => read(&metadata),
+            Err(_) => Some(Expression::Null),
+        }
+    } else {
+        None
+    }
+}
+
+/// Applies `component` to a single path argument, mapping a missing component
+/// to `null`.
+fn path_component<F>(args: Vec<Expression>, component: F) -> Option<Expression>
+where
+    F: Fn(&Path) -> Option<Cow<'_, str>>,
+{
+    if args.len() != 1 {
+        return None;
+    }
+    if let Expression::StringLiteral(path) = &args[0] {
+        Some(
+            component(Path::new(path))
+                .map(|value| Expression::StringLiteral(value.to_string()))
+                .unwrap_or(Expression::Null),
+        )
+    } else {
+        None
+    }
+}
+
+/// Resolves `.` and `..` lexically, without touching the filesystem. Leading
+/// `..` components of relative paths are kept; `..` at the root is dropped.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => normalized.push(".."),
+            },
+            other => normalized.push(other),
+        }
+    }
+    if normalized.as_os_str().is_empty() {
+        normalized.push(".");
+    }
+    normalized
+}
+
 #[cfg(test)]
 mod tests {
     use super::fs_functions;
     use crate::parser::ast::Expression;
+    use std::fs;
     use std::time::{SystemTime, UNIX_EPOCH};
 
     fn fs_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
@@ -198,6 +457,61 @@ mod tests {
         assert!(delete_file(vec![Expression::StringLiteral(path)]).is_some());
     }
 
+    #[test]
+    fn read_lines_splits_on_lf_and_crlf() {
+        let path = temp_path("lines");
+        fs::write(&path, "first\r\nsecond\nthird\n").expect("failed to write test file");
+
+        let Some(Expression::Array(lines)) =
+            fs_fn("read_lines")(vec![Expression::StringLiteral(path.clone())])
+        else {
+            panic!("read_lines should return an array");
+        };
+        assert_eq!(lines.len(), 3);
+        assert!(matches!(&lines[0], Expression::StringLiteral(line) if line == "first"));
+        assert!(matches!(&lines[2], Expression::StringLiteral(line) if line == "third"));
+
+        fs::remove_file(&path).expect("failed to remove test file");
+        assert!(fs_fn("read_lines")(vec![Expression::StringLiteral(path)]).is_none());
+    }
+
+    #[test]
+    fn copy_file_duplicates_contents_and_keeps_source() {
+        let write_file = fs_fn("write_file");
+        let read_file = fs_fn("read_file");
+        let copy_file = fs_fn("copy_file");
+
+        let source = temp_path("copy_source");
+        let destination = temp_path("copy_destination");
+        assert!(write_file(vec![
+            Expression::StringLiteral(source.clone()),
+            Expression::StringLiteral("copied".to_string())
+        ])
+        .is_some());
+
+        assert!(matches!(
+            copy_file(vec![
+                Expression::StringLiteral(source.clone()),
+                Expression::StringLiteral(destination.clone())
+            ]),
+            Some(Expression::Null)
+        ));
+        for path in [&source, &destination] {
+            assert!(matches!(
+                read_file(vec![Expression::StringLiteral(path.clone())]),
+                Some(Expression::StringLiteral(content)) if content == "copied"
+            ));
+        }
+        assert!(copy_file(vec![
+            Expression::StringLiteral(temp_path("copy_missing")),
+            Expression::StringLiteral(destination.clone())
+        ])
+        .is_none());
+
+        fs::remove_file(source).expect("failed to remove test file");
+        fs::remove_file(destination).expect("failed to remove test file");
+    }
+
     #[test]
     fn create_dir_and_list_dir_work() {
         let create_dir = fs_fn("create_dir");
@@ -219,4 +533,171 @@ mod tests {
             Some(Expression::Array(items)) if !items.is_empty()
         ));
     }
+
+    #[test]
+    fn delete_dir_removes_only_empty_directories() {
+        let delete_dir = fs_fn("delete_dir");
+        let dir = temp_path("delete_empty");
+        fs::create_dir(&dir).expect("failed to create test dir");
+        fs::write(format!("{}/x.txt", dir), "x").expect("failed to write test file");
+
+        assert!(delete_dir(vec![Expression::StringLiteral(dir.clone())]).is_none());
+        let reason = crate::stdlib::take_failure_reason().expect("missing failure reason");
+        assert!(
+            reason.starts_with(&format!("cannot delete directory '{}': ", dir)),
+            "{}",
+            reason
+        );
+        fs::remove_file(format!("{}/x.txt", dir)).expect("failed to remove test file");
+        assert!(matches!(
+            delete_dir(vec![Expression::StringLiteral(dir.clone())]),
+            Some(Expression::Null)
+        ));
+        assert!(!std::path::Path::new(&dir).exists());
+    }
+
+    #[test]
+    fn delete_dir_recursive_removes_populated_trees() {
+        let delete_dir_recursive = fs_fn("delete_dir_recursive");
+        let dir = temp_path("delete_tree");
+        fs::create_dir_all(format!("{}/nested/deeper", dir)).expect("failed to create test dirs");
+        fs::write(format!("{}/top.txt", dir), "top").expect("failed to write test file");
+        fs::write(format!("{}/nested/deeper/leaf.txt", dir), "leaf")
+            .expect("failed to write test file");
+
+        assert!(matches!(
+            delete_dir_recursive(vec![Expression::StringLiteral(dir.clone())]),
+            Some(Expression::Null)
+        ));
+        assert!(!std::path::Path::new(&dir).exists());
+        assert!(delete_dir_recursive(vec![Expression::StringLiteral(dir)]).is_none());
+    }
+
+    #[test]
+    fn metadata_reports_size_modification_time_and_kind() {
+        let write_file = fs_fn("write_file");
+        let file_size = fs_fn("file_size");
+        let file_modified = fs_fn("file_modified");
+        let is_directory = fs_fn("is_directory");
+
+        let path = temp_path("metadata");
+        let content = "hello metadata";
+        assert!(write_file(vec![
+            Expression::StringLiteral(path.clone()),
+            Expression::StringLiteral(content.to_string())
+        ])
+        .is_some());
+
+        assert!(matches!(
+            file_size(vec![Expression::StringLiteral(path.clone())]),
+            Some(Expression::Number(size)) if size as usize == content.len()
+        ));
+        assert!(matches!(
+            file_modified(vec![Expression::StringLiteral(path.clone())]),
+            Some(Expression::Float(millis)) if millis > 0.0
+        ));
+        assert!(matches!(
+            is_directory(vec![Expression::StringLiteral(path.clone())]),
+            Some(Expression::Boolean(false))
+        ));
+        assert!(matches!(
+            is_directory(vec![Expression::StringLiteral(
+                std::env::temp_dir().to_string_lossy().to_string()
+            )]),
+            Some(Expression::Boolean(true))
+        ));
+
+        fs::remove_file(&path).expect("failed to remove test file");
+        assert!(matches!(
+            file_size(vec![Expression::StringLiteral(path)]),
+            Some(Expression::Null)
+        ));
+    }
+
+    #[test]
+    fn temp_file_returns_a_fresh_path_in_the_temp_dir() {
+        let Some(Expression::StringLiteral(dir)) = fs_fn("temp_dir")(vec![]) else {
+            panic!("temp_dir should return a string");
+        };
+        let Some(Expression::StringLiteral(first)) = fs_fn("temp_file")(vec![]) else {
+            panic!("temp_file should return a string");
+        };
+        let Some(Expression::StringLiteral(second)) = fs_fn("temp_file")(vec![]) else {
+            panic!("temp_file should return a string");
+        };
+
+        assert_eq!(
+            std::path::Path::new(&first).parent(),
+            Some(std::path::Path::new(&dir))
+        );
+        assert!(!std::path::Path::new(&first).exists());
+        assert_ne!(first, second);
+    }
+
+    fn path_result(name: &str, args: &[&str]) -> String {
+        let args = args
+            .iter()
+            .map(|arg| Expression::StringLiteral(arg.to_string()))
+            .collect();
+        match fs_fn(name)(args) {
+            Some(Expression::StringLiteral(path)) => path,
+            other => panic!("{} should return a string, got {:?}", name, other),
+        }
+    }
+
+    #[test]
+    fn path_join_combines_multiple_segments() {
+        let expected: std::path::PathBuf = ["data", "logs", "today.txt"].iter().collect();
+        assert_eq!(
+            path_result("path_join", &["data", "logs", "today.txt"]),
+            expected.to_string_lossy()
+        );
+        assert!(fs_fn("path_join")(vec![Expression::Number(1)]).is_none());
+    }
+
+    fn path_part(name: &str, path: &str) -> Option<String> {
+        match fs_fn(name)(vec![Expression::StringLiteral(path.to_string())]) {
+            Some(Expression::StringLiteral(part)) => Some(part),
+            Some(Expression::Null) => None,
+            other => panic!("{} should return a string or null, got {:?}", name, other),
+        }
+    }
+
+    #[test]
+    fn path_components_of_a_normal_path() {
+        let path = "logs/2024/app.log";
+        assert_eq!(path_part("path_basename", path).as_deref(), Some("app.log"));
+        assert_eq!(
+            path_part("path_dirname", path).as_deref(),
+            Some("logs/2024")
+        );
+        assert_eq!(path_part("path_extension", path).as_deref(), Some("log"));
+    }
+
+    #[test]
+    fn path_components_without_an_extension_or_directory() {
+        assert_eq!(path_part("path_extension", "bin/vexel"), None);
+        assert_eq!(
+            path_part("path_basename", "bin/vexel").as_deref(),
+            Some("vexel")
+        );
+
+        assert_eq!(
+            path_part("path_basename", "notes.txt").as_deref(),
+            Some("notes.txt")
+        );
+        assert_eq!(path_part("path_dirname", "notes.txt"), None);
+        assert_eq!(
+            path_part("path_extension", "notes.txt").as_deref(),
+            Some("txt")
+        );
+    }
+
+    #[test]
+    fn path_normalize_resolves_dot_and_parent_components() {
+        assert_eq!(path_result("path_normalize", &["a/./b/../c"]), "a/c");
+        assert_eq!(path_result("path_normalize", &["../a/.."]), "..");
+        assert_eq!(path_result("path_normalize", &["/../x"]), "/x");
+        assert_eq!(path_result("path_normalize", &["a/.."]), ".");
+    }
 }
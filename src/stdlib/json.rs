@@ -1,43 +1,148 @@
-use super::NativeFunctionEntry;
-use crate::parser::ast::Expression;
+use super::string::scalar_text;
+use super::{fail, NativeFunctionEntry};
+use crate::parser::ast::{Expression, ObjectMap};
 use serde_json::Value;
 
 pub fn json_functions() -> Vec<NativeFunctionEntry> {
     vec![
-        ("json_parse", |args: Vec<Expression>| {
-            if args.len() == 1 {
-                if let Expression::StringLiteral(json) = &args[0] {
-                    match serde_json::from_str::<Value>(json) {
-                        Ok(value) => value_to_expression(&value),
-                        Err(_) => None,
-                    }
-                } else {
-                    None
-                }
-            } else {
-                None
+        ("json_parse", json_parse),
+        ("json_stringify", json_stringify),
+        ("from_json", json_parse),
+        ("to_json", json_stringify),
+        ("json_merge_patch", json_merge_patch),
+        ("json_path", json_path),
+    ]
+}
+
+fn json_parse(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() == 1 {
+        if let Expression::StringLiteral(json) = &args[0] {
+            match serde_json::from_str::<Value>(json) {
+                Ok(value) => value_to_expression(&value),
+                Err(_) => None,
             }
-        }),
-        ("json_stringify", |args: Vec<Expression>| {
-            if args.len() == 1 {
-                match expression_to_value(&args[0]) {
-                    Some(value) => serde_json::to_string(&value)
-                        .ok()
-                        .map(Expression::StringLiteral),
-                    None => None,
-                }
-            } else {
-                None
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
+
+fn json_stringify(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() == 1 {
+        match expression_to_value(&args[0]) {
+            Some(value) => serde_json::to_string(&value)
+                .ok()
+                .map(Expression::StringLiteral),
+            None => None,
+        }
+    } else {
+        None
+    }
+}
+
+fn json_merge_patch(args: Vec<Expression>) -> Option<Expression> {
+    match <[Expression; 2]>::try_from(args) {
+        Ok([target, patch]) => Some(merge_patch(target, patch)),
+        Err(_) => None,
+    }
+}
+
+/// Applies `patch` to `target` as described in RFC 7386: object patches merge
+/// key by key, `null` removes a key and any other patch replaces the target.
+fn merge_patch(target: Expression, patch: Expression) -> Expression {
+    let (Expression::Object(patch) | Expression::FrozenObject(patch)) = patch else {
+        return patch;
+    };
+    let mut target = match target {
+        Expression::Object(target) | Expression::FrozenObject(target) => target,
+        _ => ObjectMap::new(),
+    };
+    for (key, value) in patch {
+        if matches!(value, Expression::Null) {
+            target.shift_remove(&key);
+        } else if let Some(existing) = target.get_mut(&key) {
+            let current = std::mem::replace(existing, Expression::Null);
+            *existing = merge_patch(current, value);
+        } else {
+            target.insert(key, merge_patch(Expression::Null, value));
+        }
+    }
+    Expression::Object(target)
+}
+
+fn json_path(args: Vec<Expression>) -> Option<Expression> {
+    let (root, path) = match args.as_slice() {
+        [root, Expression::StringLiteral(path)] => (root, path),
+        _ => return None,
+    };
+    let Some(segments) = parse_path(path) else {
+        return fail(format!("invalid path '{}'", path));
+    };
+
+    let mut current = root;
+    for segment in segments {
+        let next = match (current, segment) {
+            (
+                Expression::Object(props) | Expression::FrozenObject(props),
+                PathSegment::Key(key),
+            ) => props.get(key),
+            (Expression::Array(items), PathSegment::Index(index)) => items.get(index),
+            _ => None,
+        };
+        match next {
+            Some(value) => current = value,
+            None => return Some(Expression::Null),
+        }
+    }
+    Some(current.clone())
+}
+
+enum PathSegment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+/// Splits a path such as `$.a.b[0].c` into keys and array indexes. The leading
+/// `$` is optional.
+fn parse_path(path: &str) -> Option<Vec<PathSegment<'_>>> {
+    let mut rest = path.strip_prefix('$').unwrap_or(path);
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after_dot) = rest.strip_prefix('.') {
+            let end = after_dot.find(['.', '[']).unwrap_or(after_dot.len());
+            if end == 0 {
+                return None;
             }
-        }),
-    ]
+            segments.push(PathSegment::Key(&after_dot[..end]));
+            rest = &after_dot[end..];
+        } else if let Some(after_bracket) = rest.strip_prefix('[') {
+            let end = after_bracket.find(']')?;
+            segments.push(PathSegment::Index(after_bracket[..end].parse().ok()?));
+            rest = &after_bracket[end + 1..];
+        } else if segments.is_empty() && !path.starts_with('$') {
+            // Allow a bare leading key, e.g. `a.b`.
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            segments.push(PathSegment::Key(&rest[..end]));
+            rest = &rest[end..];
+        } else {
+            return None;
+        }
+    }
+    Some(segments)
 }
 
-fn value_to_expression(value: &Value) -> Option<Expression> {
+pub(super) fn value_to_expression(value: &Value) -> Option<Expression> {
     match value {
         Value::Null => Some(Expression::Null),
         Value::Bool(b) => Some(Expression::Boolean(*b)),
-        Value::Number(n) => n.as_i64().map(|i| Expression::Number(i as i32)),
+        // Integers outside the i32 range fall back to floats instead of wrapping.
+        Value::Number(n) => n
+            .as_i64()
+            .and_then(|i| i32::try_from(i).ok())
+            .map(Expression::Number)
+            .or_else(|| n.as_f64().map(Expression::Float)),
         Value::String(s) => Some(Expression::StringLiteral(s.clone())),
         Value::Array(arr) => {
             let mut elements = Vec::new();
@@ -47,7 +152,7 @@ fn value_to_expression(value: &Value) -> Option<Expression> {
             Some(Expression::Array(elements))
         }
         Value::Object(map) => {
-            let mut props = std::collections::HashMap::new();
+            let mut props = ObjectMap::new();
             for (k, v) in map {
                 props.insert(k.clone(), value_to_expression(v)?);
             }
@@ -56,11 +161,17 @@ fn value_to_expression(value: &Value) -> Option<Expression> {
     }
 }
 
-fn expression_to_value(expr: &Expression) -> Option<Value> {
+pub(super) fn expression_to_value(expr: &Expression) -> Option<Value> {
     match expr {
-        Expression::Null => Some(Value::Null),
+        Expression::Null | Expression::Undefined => Some(Value::Null),
         Expression::Boolean(b) => Some(Value::Bool(*b)),
         Expression::Number(n) => Some(Value::Number((*n as i64).into())),
+        // JSON has no NaN or infinity; encode them as null like JavaScript does.
+        Expression::Float(n) => Some(
+            serde_json::Number::from_f64(*n)
+                .map(Value::Number)
+                .unwrap_or(Value::Null),
+        ),
         Expression::StringLiteral(s) => Some(Value::String(s.clone())),
         Expression::Array(arr) => {
             let mut vec = Vec::new();
@@ -69,13 +180,30 @@ fn expression_to_value(expr: &Expression) -> Option<Value> {
             }
             Some(Value::Array(vec))
         }
-        Expression::Object(props) => {
+        Expression::Object(props) | Expression::FrozenObject(props) => {
             let mut map = serde_json::Map::new();
             for (k, v) in props {
                 map.insert(k.clone(), expression_to_value(v)?);
             }
             Some(Value::Object(map))
         }
+        // Maps become objects keyed by the keys' printed form. A number key and a
+        // string key that print the same, such as `1` and `"1"`, cannot both be
+        // kept, so that fails instead of dropping one of them.
+        Expression::Map(entries) => {
+            let mut map = serde_json::Map::new();
+            for (key, value) in entries {
+                let key = scalar_text(key)?;
+                if map.contains_key(&key) {
+                    return fail(format!(
+                        "map has more than one key that serializes as \"{}\"",
+                        key
+                    ));
+                }
+                map.insert(key, expression_to_value(value)?);
+            }
+            Some(Value::Object(map))
+        }
         _ => None,
     }
 }
@@ -83,8 +211,7 @@ fn expression_to_value(expr: &Expression) -> Option<Value> {
 #[cfg(test)]
 mod tests {
     use super::json_functions;
-    use crate::parser::ast::Expression;
-    use std::collections::HashMap;
+    use crate::parser::ast::{Expression, ObjectMap};
 
     fn json_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
         json_functions()
@@ -105,7 +232,7 @@ mod tests {
         .expect("json_parse should return object");
         assert!(matches!(parsed, Expression::Object(_)));
 
-        let mut obj = HashMap::new();
+        let mut obj = ObjectMap::new();
         obj.insert("x".to_string(), Expression::Number(1));
         obj.insert("ok".to_string(), Expression::Boolean(true));
         let serialized = stringify(vec![Expression::Object(obj)])
@@ -122,4 +249,173 @@ mod tests {
         let result = parse(vec![Expression::StringLiteral("{invalid}".to_string())]);
         assert!(result.is_none());
     }
+
+    #[test]
+    fn stringify_handles_nested_objects_with_arrays() {
+        let stringify = json_fn("to_json");
+
+        let mut inner = ObjectMap::new();
+        inner.insert(
+            "tags".to_string(),
+            Expression::Array(vec![
+                Expression::StringLiteral("a".to_string()),
+                Expression::Float(1.5),
+                Expression::Null,
+            ]),
+        );
+        let mut obj = ObjectMap::new();
+        obj.insert("inner".to_string(), Expression::Object(inner));
+
+        assert!(matches!(
+            stringify(vec![Expression::Object(obj)]),
+            Some(Expression::StringLiteral(s)) if s == "{\"inner\":{\"tags\":[\"a\",1.5,null]}}"
+        ));
+        assert!(matches!(
+            stringify(vec![Expression::Undefined]),
+            Some(Expression::StringLiteral(s)) if s == "null"
+        ));
+    }
+
+    #[test]
+    fn stringify_encodes_maps_as_objects_with_stringified_keys() {
+        let stringify = json_fn("json_stringify");
+
+        let map = Expression::Map(vec![
+            (
+                Expression::Number(1),
+                Expression::StringLiteral("one".to_string()),
+            ),
+            (Expression::Float(2.5), Expression::Boolean(true)),
+            (
+                Expression::StringLiteral("nested".to_string()),
+                Expression::Map(vec![(Expression::Number(3), Expression::Null)]),
+            ),
+        ]);
+        assert!(matches!(
+            stringify(vec![map]),
+            Some(Expression::StringLiteral(s))
+                if s == "{\"1\":\"one\",\"2.5\":true,\"nested\":{\"3\":null}}"
+        ));
+
+        let colliding = Expression::Map(vec![
+            (Expression::Number(1), Expression::Null),
+            (Expression::StringLiteral("1".to_string()), Expression::Null),
+        ]);
+        assert!(stringify(vec![colliding]).is_none());
+        assert_eq!(
+            crate::stdlib::take_failure_reason().as_deref(),
+            Some("map has more than one key that serializes as \"1\"")
+        );
+    }
+
+    #[test]
+    fn parse_top_level_scalars() {
+        let parse = json_fn("from_json");
+
+        assert!(matches!(
+            parse(vec![Expression::StringLiteral("42".to_string())]),
+            Some(Expression::Number(42))
+        ));
+        assert!(matches!(
+            parse(vec![Expression::StringLiteral("true".to_string())]),
+            Some(Expression::Boolean(true))
+        ));
+        assert!(matches!(
+            parse(vec![Expression::StringLiteral("4294967296".to_string())]),
+            Some(Expression::Float(n)) if n == 4294967296.0
+        ));
+    }
+
+    fn merge(target: &str, patch: &str) -> String {
+        let parse = json_fn("json_parse");
+        let stringify = json_fn("json_stringify");
+        let merge_patch = json_fn("json_merge_patch");
+
+        let target = parse(vec![Expression::StringLiteral(target.to_string())]).unwrap();
+        let patch = parse(vec![Expression::StringLiteral(patch.to_string())]).unwrap();
+        match stringify(vec![merge_patch(vec![target, patch]).unwrap()]) {
+            Some(Expression::StringLiteral(json)) => json,
+            _ => panic!("Expected merged JSON"),
+        }
+    }
+
+    #[test]
+    fn merge_patch_overwrites_and_adds_keys() {
+        assert_eq!(
+            merge(r#"{"a":1,"b":"x"}"#, r#"{"b":"y","c":[1,2]}"#),
+            r#"{"a":1,"b":"y","c":[1,2]}"#
+        );
+        assert_eq!(merge(r#"{"a":1}"#, r#"[1]"#), r#"[1]"#);
+        assert_eq!(merge(r#"[1]"#, r#"{"a":1}"#), r#"{"a":1}"#);
+    }
+
+    #[test]
+    fn merge_patch_null_deletes_keys() {
+        assert_eq!(merge(r#"{"a":1,"b":2}"#, r#"{"a":null}"#), r#"{"b":2}"#);
+        assert_eq!(merge(r#"{"a":1}"#, r#"{"missing":null}"#), r#"{"a":1}"#);
+    }
+
+    #[test]
+    fn merge_patch_merges_nested_objects() {
+        assert_eq!(
+            merge(
+                r#"{"server":{"host":"localhost","port":80,"tls":true},"name":"app"}"#,
+                r#"{"server":{"port":8080,"tls":null,"debug":{"level":2}}}"#
+            ),
+            r#"{"server":{"host":"localhost","port":8080,"debug":{"level":2}},"name":"app"}"#
+        );
+    }
+
+    fn query(document: &str, path: &str) -> Option<Expression> {
+        let parse = json_fn("json_parse");
+        let json_path = json_fn("json_path");
+
+        let root = parse(vec![Expression::StringLiteral(document.to_string())]).unwrap();
+        json_path(vec![root, Expression::StringLiteral(path.to_string())])
+    }
+
+    const DOCUMENT: &str = r#"{"a":{"b":[{"c":"first"},{"c":"second"}],"n":7}}"#;
+
+    #[test]
+    fn json_path_follows_object_keys() {
+        assert!(matches!(
+            query(DOCUMENT, "$.a.n"),
+            Some(Expression::Number(7))
+        ));
+        assert!(matches!(
+            query(DOCUMENT, "a.n"),
+            Some(Expression::Number(7))
+        ));
+        assert!(matches!(query(DOCUMENT, "$"), Some(Expression::Object(_))));
+    }
+
+    #[test]
+    fn json_path_indexes_into_arrays() {
+        assert!(matches!(
+            query(DOCUMENT, "$.a.b[1].c"),
+            Some(Expression::StringLiteral(s)) if s == "second"
+        ));
+        assert!(matches!(
+            query(r#"[[1,2],[3,4]]"#, "$[1][0]"),
+            Some(Expression::Number(3))
+        ));
+    }
+
+    #[test]
+    fn json_path_returns_null_for_missing_paths_and_fails_on_bad_syntax() {
+        assert!(matches!(
+            query(DOCUMENT, "$.a.missing.c"),
+            Some(Expression::Null)
+        ));
+        assert!(matches!(
+            query(DOCUMENT, "$.a.b[5].c"),
+            Some(Expression::Null)
+        ));
+        assert!(matches!(
+            query(DOCUMENT, "$.a.n[0]"),
+            Some(Expression::Null)
+        ));
+        assert!(query(DOCUMENT, "$.a..b").is_none());
+        assert!(query(DOCUMENT, "$.a.b[x]").is_none());
+    }
 }
@@ -0,0 +1,86 @@
+use super::NativeFunctionEntry;
+use crate::parser::ast::Expression;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "debug" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "error" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref LOG_LEVEL: Mutex<LogLevel> = Mutex::new(LogLevel::Info);
+}
+
+fn set_log_level(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 1 {
+        return None;
+    }
+
+    let Expression::StringLiteral(name) = &args[0] else {
+        return None;
+    };
+
+    let level = LogLevel::parse(name)?;
+    *LOG_LEVEL.lock().ok()? = level;
+    Some(Expression::Null)
+}
+
+fn log(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 2 {
+        return None;
+    }
+
+    let (Expression::StringLiteral(level), Expression::StringLiteral(message)) =
+        (&args[0], &args[1])
+    else {
+        return None;
+    };
+
+    let level = LogLevel::parse(level)?;
+    if level >= *LOG_LEVEL.lock().ok()? {
+        eprintln!("[{}] {}", level.label(), message);
+    }
+    Some(Expression::Null)
+}
+
+pub fn logging_functions() -> Vec<NativeFunctionEntry> {
+    vec![("set_log_level", set_log_level), ("log", log)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LogLevel;
+
+    #[test]
+    fn levels_parse_case_insensitively_and_order_by_severity() {
+        assert_eq!(LogLevel::parse("WARN"), Some(LogLevel::Warn));
+        assert_eq!(LogLevel::parse("warning"), Some(LogLevel::Warn));
+        assert_eq!(LogLevel::parse("verbose"), None);
+        assert!(LogLevel::Debug < LogLevel::Info);
+        assert!(LogLevel::Warn < LogLevel::Error);
+    }
+}
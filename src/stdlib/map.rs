@@ -0,0 +1,185 @@
+use super::NativeFunctionEntry;
+use crate::parser::ast::Expression;
+
+pub fn map_functions() -> Vec<NativeFunctionEntry> {
+    vec![
+        ("map_new", map_new),
+        ("map_set", map_set),
+        ("map_get", map_get),
+        ("map_has", map_has),
+        ("map_keys", map_keys),
+    ]
+}
+
+/// Map keys are numbers or strings; numbers compare by value, so `1` and `1.0`
+/// name the same entry.
+fn is_key(key: &Expression) -> bool {
+    matches!(
+        key,
+        Expression::Number(_) | Expression::Float(_) | Expression::StringLiteral(_)
+    )
+}
+
+fn keys_equal(a: &Expression, b: &Expression) -> bool {
+    match (a, b) {
+        (Expression::StringLiteral(a), Expression::StringLiteral(b)) => a == b,
+        (Expression::Number(a), Expression::Number(b)) => a == b,
+        (Expression::Number(a), Expression::Float(b))
+        | (Expression::Float(b), Expression::Number(a)) => f64::from(*a) == *b,
+        (Expression::Float(a), Expression::Float(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn map_new(args: Vec<Expression>) -> Option<Expression> {
+    if args.is_empty() {
+        Some(Expression::Map(Vec::new()))
+    } else {
+        None
+    }
+}
+
+fn map_set(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 3 || !is_key(&args[1]) {
+        return None;
+    }
+    let mut args = args.into_iter();
+    let (Some(Expression::Map(mut entries)), Some(key), Some(value)) =
+        (args.next(), args.next(), args.next())
+    else {
+        return None;
+    };
+
+    match entries
+        .iter_mut()
+        .find(|(existing, _)| keys_equal(existing, &key))
+    {
+        Some((_, existing_value)) => *existing_value = value,
+        None => entries.push((key, value)),
+    }
+    Some(Expression::Map(entries))
+}
+
+fn map_get(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 2 {
+        return None;
+    }
+    if let Expression::Map(entries) = &args[0] {
+        if !is_key(&args[1]) {
+            return None;
+        }
+        Some(
+            entries
+                .iter()
+                .find(|(key, _)| keys_equal(key, &args[1]))
+                .map(|(_, value)| value.clone())
+                .unwrap_or(Expression::Undefined),
+        )
+    } else {
+        None
+    }
+}
+
+fn map_has(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 2 {
+        return None;
+    }
+    if let Expression::Map(entries) = &args[0] {
+        Some(Expression::Boolean(
+            entries.iter().any(|(key, _)| keys_equal(key, &args[1])),
+        ))
+    } else {
+        None
+    }
+}
+
+fn map_keys(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 1 {
+        return None;
+    }
+    if let Expression::Map(entries) = &args[0] {
+        Some(Expression::Array(
+            entries.iter().map(|(key, _)| key.clone()).collect(),
+        ))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::map_functions;
+    use crate::parser::ast::Expression;
+
+    fn map_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
+        map_functions()
+            .into_iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, f)| f)
+            .expect("missing map function")
+    }
+
+    #[test]
+    fn numeric_keys_set_get_and_overwrite() {
+        let new = map_fn("map_new");
+        let set = map_fn("map_set");
+        let get = map_fn("map_get");
+
+        let map = new(vec![]).expect("map_new should return map");
+        let map = set(vec![map, Expression::Number(1), Expression::Number(10)])
+            .expect("map_set should return map");
+        let map = set(vec![
+            map,
+            Expression::StringLiteral("1".to_string()),
+            Expression::Number(20),
+        ])
+        .expect("map_set should return map");
+        let map = set(vec![map, Expression::Float(1.0), Expression::Number(30)])
+            .expect("map_set should return map");
+
+        assert!(matches!(&map, Expression::Map(entries) if entries.len() == 2));
+        assert!(matches!(
+            get(vec![map.clone(), Expression::Number(1)]),
+            Some(Expression::Number(30))
+        ));
+        assert!(matches!(
+            get(vec![
+                map.clone(),
+                Expression::StringLiteral("1".to_string())
+            ]),
+            Some(Expression::Number(20))
+        ));
+        assert!(matches!(
+            get(vec![map, Expression::Number(2)]),
+            Some(Expression::Undefined)
+        ));
+    }
+
+    #[test]
+    fn has_and_keys_report_numeric_keys_in_insertion_order() {
+        let set = map_fn("map_set");
+        let has = map_fn("map_has");
+        let keys = map_fn("map_keys");
+
+        let map = Expression::Map(Vec::new());
+        let map = set(vec![map, Expression::Number(7), Expression::Boolean(true)])
+            .expect("map_set should return map");
+        let map = set(vec![map, Expression::Number(3), Expression::Boolean(false)])
+            .expect("map_set should return map");
+
+        assert!(matches!(
+            has(vec![map.clone(), Expression::Number(3)]),
+            Some(Expression::Boolean(true))
+        ));
+        assert!(matches!(
+            has(vec![map.clone(), Expression::Number(4)]),
+            Some(Expression::Boolean(false))
+        ));
+        assert!(matches!(
+            keys(vec![map.clone()]),
+            Some(Expression::Array(items))
+                if matches!(items.as_slice(), [Expression::Number(7), Expression::Number(3)])
+        ));
+        assert!(set(vec![map, Expression::Null, Expression::Number(1)]).is_none());
+    }
+}
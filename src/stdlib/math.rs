@@ -1,4 +1,4 @@
-use super::NativeFunctionEntry;
+use super::{as_float, fail, NativeFunctionEntry};
 use crate::parser::ast::Expression;
 
 pub fn math_functions() -> Vec<NativeFunctionEntry> {
@@ -9,7 +9,7 @@ pub fn math_functions() -> Vec<NativeFunctionEntry> {
                     (Expression::Number(a), Expression::Number(b)) => {
                         Some(Expression::Number(a + b))
                     }
-                    _ => None,
+                    (a, b) => Some(Expression::Float(as_float(a)? + as_float(b)?)),
                 }
             } else {
                 None
@@ -21,7 +21,7 @@ pub fn math_functions() -> Vec<NativeFunctionEntry> {
                     (Expression::Number(a), Expression::Number(b)) => {
                         Some(Expression::Number(a - b))
                     }
-                    _ => None,
+                    (a, b) => Some(Expression::Float(as_float(a)? - as_float(b)?)),
                 }
             } else {
                 None
@@ -33,7 +33,7 @@ pub fn math_functions() -> Vec<NativeFunctionEntry> {
                     (Expression::Number(a), Expression::Number(b)) => {
                         Some(Expression::Number(a * b))
                     }
-                    _ => None,
+                    (a, b) => Some(Expression::Float(as_float(a)? * as_float(b)?)),
                 }
             } else {
                 None
@@ -42,10 +42,18 @@ pub fn math_functions() -> Vec<NativeFunctionEntry> {
         ("math_divide", |args: Vec<Expression>| {
             if args.len() == 2 {
                 match (&args[0], &args[1]) {
-                    (Expression::Number(a), Expression::Number(b)) if *b != 0 => {
+                    (Expression::Number(_), Expression::Number(0)) => fail("division by zero"),
+                    (Expression::Number(a), Expression::Number(b)) => {
                         Some(Expression::Number(a / b))
                     }
-                    _ => None,
+                    (a, b) => {
+                        let (a, b) = (as_float(a)?, as_float(b)?);
+                        if b == 0.0 {
+                            fail("division by zero")
+                        } else {
+                            Some(Expression::Float(a / b))
+                        }
+                    }
                 }
             } else {
                 None
@@ -57,7 +65,15 @@ pub fn math_functions() -> Vec<NativeFunctionEntry> {
                     (Expression::Number(a), Expression::Number(b)) if *b >= 0 => {
                         Some(Expression::Number(a.pow(*b as u32)))
                     }
-                    _ => None,
+                    // Fractional or negative exponents widen to a float result.
+                    (a, b) => {
+                        let result = as_float(a)?.powf(as_float(b)?);
+                        if result.is_finite() {
+                            Some(Expression::Float(result))
+                        } else {
+                            fail("result is not a finite number")
+                        }
+                    }
                 }
             } else {
                 None
@@ -67,18 +83,56 @@ pub fn math_functions() -> Vec<NativeFunctionEntry> {
             if args.len() == 1 {
                 match &args[0] {
                     Expression::Number(a) if *a >= 0 => {
-                        Some(Expression::Number(((*a as f64).sqrt()) as i32))
+                        // Perfect squares keep an integer root; anything else
+                        // has no exact integer root and returns a float.
+                        let root = (*a as f64).sqrt();
+                        if root.fract() == 0.0 {
+                            Some(Expression::Number(root as i32))
+                        } else {
+                            Some(Expression::Float(root))
+                        }
                     }
+                    Expression::Float(a) if *a >= 0.0 => Some(Expression::Float(a.sqrt())),
                     _ => None,
                 }
             } else {
                 None
             }
         }),
+        ("math_pi", |args: Vec<Expression>| {
+            if args.is_empty() {
+                Some(Expression::Float(std::f64::consts::PI))
+            } else {
+                None
+            }
+        }),
+        ("math_e", |args: Vec<Expression>| {
+            if args.is_empty() {
+                Some(Expression::Float(std::f64::consts::E))
+            } else {
+                None
+            }
+        }),
         ("math_abs", |args: Vec<Expression>| {
             if args.len() == 1 {
                 match &args[0] {
                     Expression::Number(a) => Some(Expression::Number(a.abs())),
+                    Expression::Float(a) => Some(Expression::Float(a.abs())),
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        }),
+        ("math_round_to", |args: Vec<Expression>| {
+            if args.len() == 2 {
+                let value = match &args[0] {
+                    Expression::Number(a) => f64::from(*a),
+                    Expression::Float(a) => *a,
+                    _ => return None,
+                };
+                match &args[1] {
+                    Expression::Number(places) => Some(Expression::Float(round_to(value, *places))),
                     _ => None,
                 }
             } else {
@@ -88,6 +142,27 @@ pub fn math_functions() -> Vec<NativeFunctionEntry> {
     ]
 }
 
+/// Rounds to `places` decimal places, with halves rounding away from zero.
+/// Negative `places` round to tens, hundreds and so on.
+pub(super) fn round_to(value: f64, places: i32) -> f64 {
+    // Beyond f64's decimal exponent range the factor would overflow; rounding to
+    // that many places keeps every value, or rounds every value to zero.
+    let places = places.clamp(-f64::MAX_10_EXP, f64::MAX_10_EXP);
+    // Scaling by 10^places and back keeps the factor an exact integer in both
+    // directions, which avoids the error of multiplying by e.g. 0.01.
+    let factor = 10f64.powi(places.unsigned_abs() as i32);
+    let rounded = if places >= 0 {
+        (value * factor).round() / factor
+    } else {
+        (value / factor).round() * factor
+    };
+    if rounded.is_finite() {
+        rounded
+    } else {
+        value
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::math_functions;
@@ -116,12 +191,65 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn pi_and_e_return_float_constants() {
+        let pi = math_fn("math_pi");
+        let e = math_fn("math_e");
+
+        assert!(
+            matches!(pi(vec![]), Some(Expression::Float(value)) if value == std::f64::consts::PI)
+        );
+        assert!(
+            matches!(e(vec![]), Some(Expression::Float(value)) if value == std::f64::consts::E)
+        );
+        assert!(pi(vec![Expression::Number(1)]).is_none());
+    }
+
     #[test]
     fn divide_by_zero_returns_none() {
         let divide = math_fn("math_divide");
         assert!(divide(vec![Expression::Number(8), Expression::Number(0)]).is_none());
     }
 
+    #[test]
+    fn round_to_handles_decimal_places_halves_and_negative_places() {
+        let round_to = math_fn("math_round_to");
+
+        assert!(matches!(
+            round_to(vec![Expression::Float(12.3456), Expression::Number(2)]),
+            Some(Expression::Float(value)) if value == 12.35
+        ));
+        assert!(matches!(
+            round_to(vec![Expression::Float(0.125), Expression::Number(2)]),
+            Some(Expression::Float(value)) if value == 0.13
+        ));
+        assert!(matches!(
+            round_to(vec![Expression::Float(2.5), Expression::Number(0)]),
+            Some(Expression::Float(value)) if value == 3.0
+        ));
+        assert!(matches!(
+            round_to(vec![Expression::Number(1250), Expression::Number(-2)]),
+            Some(Expression::Float(value)) if value == 1300.0
+        ));
+        assert!(round_to(vec![Expression::Float(1.5), Expression::Float(1.0)]).is_none());
+    }
+
+    #[test]
+    fn round_to_clamps_extreme_places() {
+        let round_to = math_fn("math_round_to");
+
+        for places in [-400, i32::MIN] {
+            assert!(matches!(
+                round_to(vec![Expression::Float(123.5), Expression::Number(places)]),
+                Some(Expression::Float(value)) if value == 0.0
+            ));
+        }
+        assert!(matches!(
+            round_to(vec![Expression::Float(1.25), Expression::Number(i32::MAX)]),
+            Some(Expression::Float(value)) if value == 1.25
+        ));
+    }
+
     #[test]
     fn power_and_abs_return_expected_values() {
         let power = math_fn("math_power");
@@ -136,4 +264,76 @@ mod tests {
             Some(Expression::Number(9))
         ));
     }
+
+    #[test]
+    fn arithmetic_widens_mixed_integer_and_float_operands() {
+        let float_of = |name: &str, args: Vec<Expression>| match math_fn(name)(args) {
+            Some(Expression::Float(value)) => value,
+            other => panic!("{} returned {:?}", name, other),
+        };
+
+        assert_eq!(
+            float_of(
+                "math_add",
+                vec![Expression::Float(1.5), Expression::Number(1)]
+            ),
+            2.5
+        );
+        assert_eq!(
+            float_of(
+                "math_subtract",
+                vec![Expression::Number(1), Expression::Float(0.25)]
+            ),
+            0.75
+        );
+        assert_eq!(
+            float_of(
+                "math_multiply",
+                vec![Expression::Float(1.5), Expression::Number(2)]
+            ),
+            3.0
+        );
+        assert_eq!(
+            float_of(
+                "math_divide",
+                vec![Expression::Float(3.0), Expression::Number(2)]
+            ),
+            1.5
+        );
+        assert_eq!(
+            float_of(
+                "math_power",
+                vec![Expression::Number(2), Expression::Float(0.5)]
+            ),
+            std::f64::consts::SQRT_2
+        );
+        assert_eq!(
+            float_of(
+                "math_power",
+                vec![Expression::Number(2), Expression::Number(-1)]
+            ),
+            0.5
+        );
+        assert_eq!(float_of("math_abs", vec![Expression::Float(-1.5)]), 1.5);
+        assert!(
+            math_fn("math_divide")(vec![Expression::Float(1.0), Expression::Number(0)]).is_none()
+        );
+        assert!(
+            math_fn("math_add")(vec![Expression::Float(1.0), Expression::Boolean(true)]).is_none()
+        );
+    }
+    #[test]
+    fn sqrt_returns_integers_only_for_perfect_squares() {
+        let sqrt = math_fn("math_sqrt");
+
+        assert!(matches!(
+            sqrt(vec![Expression::Number(16)]),
+            Some(Expression::Number(4))
+        ));
+        assert!(matches!(
+            sqrt(vec![Expression::Number(2)]),
+            Some(Expression::Float(value)) if value == std::f64::consts::SQRT_2
+        ));
+        assert!(sqrt(vec![Expression::Number(-4)]).is_none());
+    }
 }
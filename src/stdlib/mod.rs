@@ -1,30 +1,76 @@
 use crate::parser::ast;
+use std::cell::RefCell;
 
 pub type NativeFunction = fn(Vec<ast::Expression>) -> Option<ast::Expression>;
 pub type NativeFunctionEntry = (&'static str, NativeFunction);
 
 pub mod array;
+pub mod bits;
 pub mod core;
 pub mod debug;
 pub mod fs;
 pub mod json;
+pub mod logging;
+pub mod map;
 pub mod math;
 pub mod net;
 mod object;
+pub mod random;
+pub mod regex;
 pub mod string;
 pub mod thread;
+pub mod time;
+pub mod toml;
+pub mod ws;
+pub mod yaml;
+
+thread_local! {
+    static FAILURE_REASON: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Fails the current native call with a reason that the runtime includes in the
+/// error message. Natives that simply return `None` get a generic message.
+pub fn fail<T>(reason: impl Into<String>) -> Option<T> {
+    let reason = reason.into();
+    FAILURE_REASON.with(|slot| *slot.borrow_mut() = Some(reason));
+    None
+}
+
+/// Widens integers and floats to `f64` for natives that accept either.
+pub(crate) fn as_float(value: &ast::Expression) -> Option<f64> {
+    match value {
+        ast::Expression::Number(n) => Some(*n as f64),
+        ast::Expression::Float(n) => Some(*n),
+        _ => None,
+    }
+}
+
+/// Takes the reason recorded by the last failed native call on this thread.
+pub(crate) fn take_failure_reason() -> Option<String> {
+    FAILURE_REASON.with(|slot| slot.borrow_mut().take())
+}
 
 pub fn get_all_native_functions() -> Vec<NativeFunctionEntry> {
     let mut functions = Vec::new();
     functions.extend(math::math_functions());
+    functions.extend(bits::bit_functions());
+    functions.extend(random::random_functions());
     functions.extend(array::array_functions());
     functions.extend(debug::debug_functions());
     functions.extend(string::string_functions());
+    functions.extend(regex::regex_functions());
     functions.extend(net::http_functions());
+    functions.extend(net::tcp::tcp_functions());
+    functions.extend(ws::ws_functions());
     functions.extend(core::core_functions());
+    functions.extend(time::time_functions());
     functions.extend(object::object_functions());
+    functions.extend(map::map_functions());
     functions.extend(json::json_functions());
+    functions.extend(yaml::yaml_functions());
+    functions.extend(toml::toml_functions());
     functions.extend(fs::fs_functions());
     functions.extend(thread::thread_functions());
+    functions.extend(logging::logging_functions());
     functions
 }
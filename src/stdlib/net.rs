@@ -1,6 +1,78 @@
-use super::NativeFunctionEntry;
-use crate::parser::ast::Expression;
-use reqwest::blocking::Client;
+pub mod tcp;
+
+use super::json::{expression_to_value, value_to_expression};
+use super::string::scalar_text;
+use super::{fail, NativeFunctionEntry};
+use crate::parser::ast::{Expression, ObjectMap};
+use reqwest::blocking::{Client, Response};
+use reqwest::header::CONTENT_TYPE;
+use std::fs::File;
+use std::thread;
+use std::time::Duration;
+
+/// Sends a GET request up to `attempts` times, sleeping between attempts with a
+/// delay that starts at `backoff` and doubles each time. Only transport errors
+/// such as refused or dropped connections are retried; any HTTP response counts
+/// as a success.
+fn get_with_retry(url: &str, attempts: u32, backoff: Duration) -> Option<String> {
+    let client = Client::new();
+    let mut delay = backoff;
+    for attempt in 1..=attempts {
+        if let Ok(response) = client.get(url).send() {
+            return response.text().ok();
+        }
+        if attempt < attempts {
+            thread::sleep(delay);
+            delay = delay.saturating_mul(2);
+        }
+    }
+    None
+}
+
+/// Streams the body of a successful GET response into `path` without buffering
+/// it in memory. Error statuses are reported instead of being written out.
+fn download(url: &str, path: &str) -> Result<(), String> {
+    let mut response = Client::new()
+        .get(url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| error.to_string())?;
+    let mut file =
+        File::create(path).map_err(|error| format!("cannot create '{}': {}", path, error))?;
+    response
+        .copy_to(&mut file)
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+/// Converts object properties into form fields. Scalars use their printed form;
+/// arrays, objects and undefined values are rejected.
+fn form_fields(fields: &ObjectMap) -> Option<Vec<(&str, String)>> {
+    fields
+        .iter()
+        .map(|(key, value)| Some((key.as_str(), scalar_text(value)?)))
+        .collect()
+}
+
+/// Parses the body when the response declares a JSON content type and falls
+/// back to the raw text otherwise, including when the JSON is malformed.
+fn json_or_text(response: Response) -> Option<Expression> {
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("json"));
+    let body = response.text().ok()?;
+    if is_json {
+        if let Some(parsed) = serde_json::from_str(&body)
+            .ok()
+            .and_then(|value| value_to_expression(&value))
+        {
+            return Some(parsed);
+        }
+    }
+    Some(Expression::StringLiteral(body))
+}
 
 pub fn http_functions() -> Vec<NativeFunctionEntry> {
     vec![
@@ -22,6 +94,41 @@ pub fn http_functions() -> Vec<NativeFunctionEntry> {
                 None
             }
         }),
+        ("http_get_retry", |args: Vec<Expression>| {
+            if args.len() == 3 {
+                match (&args[0], &args[1], &args[2]) {
+                    (
+                        Expression::StringLiteral(url),
+                        Expression::Number(attempts),
+                        Expression::Number(backoff_ms),
+                    ) if *attempts >= 1 && *backoff_ms >= 0 => get_with_retry(
+                        url,
+                        *attempts as u32,
+                        Duration::from_millis(*backoff_ms as u64),
+                    )
+                    .map(Expression::StringLiteral),
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        }),
+        ("download_file", |args: Vec<Expression>| {
+            if args.len() == 2 {
+                if let (Expression::StringLiteral(url), Expression::StringLiteral(path)) =
+                    (&args[0], &args[1])
+                {
+                    match download(url, path) {
+                        Ok(()) => Some(Expression::Null),
+                        Err(reason) => fail(reason),
+                    }
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
         ("http_post", |args: Vec<Expression>| {
             if args.len() == 2 {
                 if let (Expression::StringLiteral(url), Expression::StringLiteral(body)) =
@@ -42,6 +149,50 @@ pub fn http_functions() -> Vec<NativeFunctionEntry> {
                 None
             }
         }),
+        ("http_post_form", |args: Vec<Expression>| {
+            if args.len() == 2 {
+                if let (
+                    Expression::StringLiteral(url),
+                    Expression::Object(fields) | Expression::FrozenObject(fields),
+                ) = (&args[0], &args[1])
+                {
+                    let fields = form_fields(fields)?;
+                    let client = Client::new();
+                    match client.post(url).form(&fields).send() {
+                        Ok(response) => match response.text() {
+                            Ok(body) => Some(Expression::StringLiteral(body)),
+                            Err(_) => None,
+                        },
+                        Err(_) => None,
+                    }
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
+        ("http_post_json", |args: Vec<Expression>| {
+            if args.len() == 2 {
+                if let Expression::StringLiteral(url) = &args[0] {
+                    let body = serde_json::to_string(&expression_to_value(&args[1])?).ok()?;
+                    let client = Client::new();
+                    match client
+                        .post(url)
+                        .header(CONTENT_TYPE, "application/json")
+                        .body(body)
+                        .send()
+                    {
+                        Ok(response) => json_or_text(response),
+                        Err(_) => None,
+                    }
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
         ("http_put", |args: Vec<Expression>| {
             if args.len() == 2 {
                 if let (Expression::StringLiteral(url), Expression::StringLiteral(body)) =
@@ -86,7 +237,15 @@ pub fn http_functions() -> Vec<NativeFunctionEntry> {
 #[cfg(test)]
 mod tests {
     use super::http_functions;
-    use crate::parser::ast::Expression;
+    use crate::parser::ast::{Expression, ObjectMap};
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{SystemTime, UNIX_EPOCH};
 
     fn http_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
         http_functions()
@@ -103,6 +262,215 @@ mod tests {
         assert!(result.is_none());
     }
 
+    /// Serves `response` to a single connection and hands back the raw request.
+    fn serve_once(response: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            loop {
+                let read = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(header_end) = text.find("\r\n\r\n") {
+                    let content_length = text[..header_end]
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= header_end + 4 + content_length || read == 0 {
+                        break;
+                    }
+                }
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+        (url, handle)
+    }
+
+    fn temp_path(name: &str) -> String {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock before unix epoch")
+            .as_nanos();
+        std::env::temp_dir()
+            .join(format!(
+                "vexel_net_test_{}_{}_{}",
+                name,
+                std::process::id(),
+                nanos
+            ))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn download_file_streams_body_to_disk() {
+        let (url, server) = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Length: 13\r\nConnection: close\r\n\r\nhello, vexel!",
+        );
+        let path = temp_path("download");
+
+        let download_file = http_fn("download_file");
+        let result = download_file(vec![
+            Expression::StringLiteral(format!("{}/file.txt", url)),
+            Expression::StringLiteral(path.clone()),
+        ]);
+
+        assert!(matches!(result, Some(Expression::Null)));
+        assert!(server.join().unwrap().starts_with("GET /file.txt "));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hello, vexel!");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn download_file_fails_on_error_status_without_writing() {
+        let (url, server) =
+            serve_once("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        let path = temp_path("download_missing");
+
+        let download_file = http_fn("download_file");
+        let result = download_file(vec![
+            Expression::StringLiteral(url),
+            Expression::StringLiteral(path.clone()),
+        ]);
+        server.join().unwrap();
+
+        assert!(result.is_none());
+        assert!(!Path::new(&path).exists());
+    }
+
+    #[test]
+    fn post_form_url_encodes_fields() {
+        let (url, server) =
+            serve_once("HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok");
+
+        let mut fields = ObjectMap::new();
+        fields.insert(
+            "name".to_string(),
+            Expression::StringLiteral("Ada Lovelace".to_string()),
+        );
+        fields.insert("age".to_string(), Expression::Number(36));
+        fields.insert(
+            "note".to_string(),
+            Expression::StringLiteral("a&b=c".to_string()),
+        );
+
+        let post_form = http_fn("http_post_form");
+        let result = post_form(vec![
+            Expression::StringLiteral(url),
+            Expression::Object(fields),
+        ]);
+
+        assert!(matches!(result, Some(Expression::StringLiteral(body)) if body == "ok"));
+        let request = server.join().unwrap().to_ascii_lowercase();
+        assert!(request.starts_with("post / "));
+        assert!(request.contains("content-type: application/x-www-form-urlencoded"));
+        assert!(request.ends_with("\r\n\r\nname=ada+lovelace&age=36&note=a%26b%3dc"));
+    }
+
+    #[test]
+    fn post_form_rejects_nested_values() {
+        let mut fields = ObjectMap::new();
+        fields.insert("tags".to_string(), Expression::Array(Vec::new()));
+
+        let post_form = http_fn("http_post_form");
+        assert!(post_form(vec![
+            Expression::StringLiteral("http://127.0.0.1:1/".to_string()),
+            Expression::Object(fields),
+        ])
+        .is_none());
+    }
+
+    #[test]
+    fn post_json_sends_json_and_parses_json_responses() {
+        let (url, server) = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 10\r\nConnection: close\r\n\r\n{\"id\": 42}",
+        );
+
+        let mut payload = ObjectMap::new();
+        payload.insert(
+            "name".to_string(),
+            Expression::StringLiteral("vexel".to_string()),
+        );
+        payload.insert(
+            "tags".to_string(),
+            Expression::Array(vec![Expression::Number(1), Expression::Boolean(true)]),
+        );
+
+        let post_json = http_fn("http_post_json");
+        let result = post_json(vec![
+            Expression::StringLiteral(url),
+            Expression::Object(payload),
+        ]);
+
+        assert!(matches!(
+            result,
+            Some(Expression::Object(response))
+                if matches!(response.get("id"), Some(Expression::Number(42)))
+        ));
+        let request = server.join().unwrap();
+        assert!(request
+            .to_ascii_lowercase()
+            .contains("content-type: application/json"));
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(body).expect("body is valid JSON");
+        assert_eq!(
+            parsed,
+            serde_json::json!({"name": "vexel", "tags": [1, true]})
+        );
+    }
+
+    #[test]
+    fn post_json_returns_text_for_non_json_responses() {
+        let (url, server) = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 7\r\nConnection: close\r\n\r\ncreated",
+        );
+
+        let post_json = http_fn("http_post_json");
+        let result = post_json(vec![Expression::StringLiteral(url), Expression::Null]);
+        server.join().unwrap();
+
+        assert!(matches!(result, Some(Expression::StringLiteral(body)) if body == "created"));
+    }
+
+    #[test]
+    fn get_retry_gives_up_after_the_configured_attempts() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::clone(&connections);
+        // Accept and immediately drop every connection so each request fails.
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                seen.fetch_add(1, Ordering::SeqCst);
+                drop(stream);
+            }
+        });
+
+        let get_retry = http_fn("http_get_retry");
+        let result = get_retry(vec![
+            Expression::StringLiteral(url),
+            Expression::Number(3),
+            Expression::Number(1),
+        ]);
+
+        assert!(result.is_none());
+        assert_eq!(connections.load(Ordering::SeqCst), 3);
+        assert!(get_retry(vec![
+            Expression::StringLiteral("http://127.0.0.1:1/".to_string()),
+            Expression::Number(0),
+            Expression::Number(1),
+        ])
+        .is_none());
+    }
+
     #[test]
     fn wrong_argument_types_return_none() {
         let post = http_fn("http_post");
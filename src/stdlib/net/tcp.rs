@@ -0,0 +1,227 @@
+use crate::parser::ast::Expression;
+use crate::stdlib::{fail, NativeFunctionEntry};
+use rustc_hash::FxHashMap as HashMap;
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    static ref STREAMS: Mutex<HashMap<String, Arc<TcpStream>>> = Mutex::new(HashMap::default());
+}
+
+static NEXT_STREAM_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Upper bound on one `tcp_read`, so a large `max_bytes` cannot make the
+/// interpreter allocate an arbitrarily large buffer.
+const MAX_READ_BYTES: usize = 64 * 1024;
+
+pub fn tcp_functions() -> Vec<NativeFunctionEntry> {
+    vec![
+        ("tcp_connect", tcp_connect),
+        ("tcp_write", tcp_write),
+        ("tcp_read", tcp_read),
+        ("tcp_close", tcp_close),
+    ]
+}
+
+/// Reads and writes go through `&TcpStream`, so a blocking `tcp_read` does not
+/// stop another thread from writing to the same connection.
+fn stream(id: &str) -> Option<Arc<TcpStream>> {
+    match STREAMS.lock().ok()?.get(id) {
+        Some(stream) => Some(Arc::clone(stream)),
+        None => fail(format!("unknown connection '{}'", id)),
+    }
+}
+
+fn tcp_connect(args: Vec<Expression>) -> Option<Expression> {
+    let [Expression::StringLiteral(host), Expression::Number(port)] = args.as_slice() else {
+        return None;
+    };
+    let port = u16::try_from(*port).ok()?;
+    let stream = match TcpStream::connect((host.as_str(), port)) {
+        Ok(stream) => stream,
+        Err(error) => return fail(format!("cannot connect to {}:{}: {}", host, port, error)),
+    };
+
+    let id = format!("tcp{}", NEXT_STREAM_ID.fetch_add(1, Ordering::Relaxed));
+    STREAMS.lock().ok()?.insert(id.clone(), Arc::new(stream));
+    Some(Expression::StringLiteral(id))
+}
+
+fn tcp_write(args: Vec<Expression>) -> Option<Expression> {
+    let [Expression::StringLiteral(id), Expression::StringLiteral(data)] = args.as_slice() else {
+        return None;
+    };
+    let stream = stream(id)?;
+    match (&*stream).write_all(data.as_bytes()) {
+        Ok(()) => Some(Expression::Null),
+        Err(error) => fail(error.to_string()),
+    }
+}
+
+/// Blocks until data arrives and returns at most `max_bytes` of it (capped at
+/// `MAX_READ_BYTES`), decoded as lossy UTF-8. Returns `null` once the peer has
+/// closed the connection.
+fn tcp_read(args: Vec<Expression>) -> Option<Expression> {
+    let [Expression::StringLiteral(id), Expression::Number(max_bytes)] = args.as_slice() else {
+        return None;
+    };
+    if *max_bytes < 1 {
+        return None;
+    }
+    let stream = stream(id)?;
+    let mut buffer = vec![0; (*max_bytes as usize).min(MAX_READ_BYTES)];
+    match (&*stream).read(&mut buffer) {
+        Ok(0) => Some(Expression::Null),
+        Ok(read) => Some(Expression::StringLiteral(
+            String::from_utf8_lossy(&buffer[..read]).into_owned(),
+        )),
+        Err(error) => fail(error.to_string()),
+    }
+}
+
+fn tcp_close(args: Vec<Expression>) -> Option<Expression> {
+    let [Expression::StringLiteral(id)] = args.as_slice() else {
+        return None;
+    };
+    let Some(stream) = STREAMS.lock().ok()?.remove(id) else {
+        return fail(format!("unknown connection '{}'", id));
+    };
+    // The peer may already have closed its side; the handle is released either way.
+    let _ = stream.shutdown(Shutdown::Both);
+    Some(Expression::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tcp_functions, MAX_READ_BYTES};
+    use crate::parser::ast::Expression;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn tcp_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
+        tcp_functions()
+            .into_iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, f)| f)
+            .expect("missing tcp function")
+    }
+
+    /// Accepts one client and echoes bytes back until it disconnects.
+    fn echo_server() -> (i32, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port() as i32;
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = [0; 256];
+            loop {
+                match stream.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => stream.write_all(&buffer[..read]).unwrap(),
+                }
+            }
+        });
+        (port, handle)
+    }
+
+    #[test]
+    fn written_data_is_echoed_back_over_loopback() {
+        let (port, server) = echo_server();
+        let connect = tcp_fn("tcp_connect");
+        let write = tcp_fn("tcp_write");
+        let read = tcp_fn("tcp_read");
+        let close = tcp_fn("tcp_close");
+
+        let handle = connect(vec![
+            Expression::StringLiteral("127.0.0.1".to_string()),
+            Expression::Number(port),
+        ])
+        .expect("tcp_connect failed");
+
+        assert!(matches!(
+            write(vec![
+                handle.clone(),
+                Expression::StringLiteral("ping".to_string())
+            ]),
+            Some(Expression::Null)
+        ));
+        let mut echoed = String::new();
+        while echoed.len() < 4 {
+            match read(vec![handle.clone(), Expression::Number(64)]) {
+                Some(Expression::StringLiteral(chunk)) => echoed.push_str(&chunk),
+                _ => panic!("Expected echoed data"),
+            }
+        }
+        assert_eq!(echoed, "ping");
+
+        assert!(matches!(
+            close(vec![handle.clone()]),
+            Some(Expression::Null)
+        ));
+        server.join().unwrap();
+        assert!(read(vec![handle, Expression::Number(64)]).is_none());
+    }
+
+    #[test]
+    fn large_read_sizes_are_capped() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port() as i32;
+        let sent = MAX_READ_BYTES * 2 + 10;
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.write_all(&vec![b'x'; sent]).unwrap();
+        });
+
+        let handle = tcp_fn("tcp_connect")(vec![
+            Expression::StringLiteral("127.0.0.1".to_string()),
+            Expression::Number(port),
+        ])
+        .expect("tcp_connect failed");
+        let read = tcp_fn("tcp_read");
+        let mut received = 0;
+        while let Some(Expression::StringLiteral(chunk)) =
+            read(vec![handle.clone(), Expression::Number(i32::MAX)])
+        {
+            assert!(chunk.len() <= MAX_READ_BYTES);
+            received += chunk.len();
+        }
+        assert_eq!(received, sent);
+        server.join().unwrap();
+        tcp_fn("tcp_close")(vec![handle]);
+    }
+
+    #[test]
+    fn read_returns_null_after_the_peer_closes() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port() as i32;
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.write_all(b"bye").unwrap();
+        });
+
+        let handle = tcp_fn("tcp_connect")(vec![
+            Expression::StringLiteral("127.0.0.1".to_string()),
+            Expression::Number(port),
+        ])
+        .expect("tcp_connect failed");
+        server.join().unwrap();
+
+        let read = tcp_fn("tcp_read");
+        assert!(matches!(
+            read(vec![handle.clone(), Expression::Number(1)]),
+            Some(Expression::StringLiteral(chunk)) if chunk == "b"
+        ));
+        assert!(matches!(
+            read(vec![handle.clone(), Expression::Number(16)]),
+            Some(Expression::StringLiteral(chunk)) if chunk == "ye"
+        ));
+        assert!(matches!(
+            read(vec![handle.clone(), Expression::Number(16)]),
+            Some(Expression::Null)
+        ));
+        assert!(read(vec![handle.clone(), Expression::Number(0)]).is_none());
+        tcp_fn("tcp_close")(vec![handle]);
+    }
+}
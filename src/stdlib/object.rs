@@ -1,5 +1,5 @@
 use super::NativeFunctionEntry;
-use crate::parser::ast::Expression;
+use crate::parser::ast::{format_float, Expression, ObjectMap};
 
 pub fn object_functions() -> Vec<NativeFunctionEntry> {
     vec![
@@ -7,6 +7,7 @@ pub fn object_functions() -> Vec<NativeFunctionEntry> {
             if args.len() == 1 {
                 match &args[0] {
                     Expression::Object(_)
+                    | Expression::FrozenObject(_)
                     | Expression::Array(_)
                     | Expression::Number(_)
                     | Expression::Boolean(_)
@@ -22,7 +23,9 @@ pub fn object_functions() -> Vec<NativeFunctionEntry> {
         }),
         ("object_keys", |args: Vec<Expression>| {
             if args.len() == 1 {
-                if let Expression::Object(properties) = &args[0] {
+                if let Expression::Object(properties) | Expression::FrozenObject(properties) =
+                    &args[0]
+                {
                     let keys = properties
                         .keys()
                         .map(|key| Expression::StringLiteral(key.clone()))
@@ -37,7 +40,9 @@ pub fn object_functions() -> Vec<NativeFunctionEntry> {
         }),
         ("object_values", |args: Vec<Expression>| {
             if args.len() == 1 {
-                if let Expression::Object(properties) = &args[0] {
+                if let Expression::Object(properties) | Expression::FrozenObject(properties) =
+                    &args[0]
+                {
                     let values = properties.values().cloned().collect();
                     Some(Expression::Array(values))
                 } else {
@@ -49,8 +54,10 @@ pub fn object_functions() -> Vec<NativeFunctionEntry> {
         }),
         ("object_has_property", |args: Vec<Expression>| {
             if args.len() == 2 {
-                if let (Expression::Object(properties), Expression::StringLiteral(key)) =
-                    (&args[0], &args[1])
+                if let (
+                    Expression::Object(properties) | Expression::FrozenObject(properties),
+                    Expression::StringLiteral(key),
+                ) = (&args[0], &args[1])
                 {
                     let has_key = properties.contains_key(key);
                     Some(Expression::Boolean(has_key))
@@ -63,8 +70,10 @@ pub fn object_functions() -> Vec<NativeFunctionEntry> {
         }),
         ("object_merge", |args: Vec<Expression>| {
             if args.len() == 2 {
-                if let (Expression::Object(props1), Expression::Object(props2)) =
-                    (&args[0], &args[1])
+                if let (
+                    Expression::Object(props1) | Expression::FrozenObject(props1),
+                    Expression::Object(props2) | Expression::FrozenObject(props2),
+                ) = (&args[0], &args[1])
                 {
                     let mut result = props1.clone();
                     result.extend(props2.clone());
@@ -78,7 +87,7 @@ pub fn object_functions() -> Vec<NativeFunctionEntry> {
         }),
         ("object_create", |args: Vec<Expression>| {
             if args.len().is_multiple_of(2) {
-                let mut properties = std::collections::HashMap::new();
+                let mut properties = ObjectMap::new();
 
                 for i in (0..args.len()).step_by(2) {
                     if let Expression::StringLiteral(key) = &args[i] {
@@ -93,12 +102,116 @@ pub fn object_functions() -> Vec<NativeFunctionEntry> {
                 None
             }
         }),
+        ("object_entries", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                if let Expression::Object(properties) | Expression::FrozenObject(properties) =
+                    &args[0]
+                {
+                    let entries = properties
+                        .iter()
+                        .map(|(key, value)| {
+                            Expression::Array(vec![
+                                Expression::StringLiteral(key.clone()),
+                                value.clone(),
+                            ])
+                        })
+                        .collect();
+                    Some(Expression::Array(entries))
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
+        ("object_size", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                if let Expression::Object(properties) | Expression::FrozenObject(properties) =
+                    &args[0]
+                {
+                    Some(Expression::Number(properties.len() as i32))
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
+        ("object_is_empty", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                if let Expression::Object(properties) | Expression::FrozenObject(properties) =
+                    &args[0]
+                {
+                    Some(Expression::Boolean(properties.is_empty()))
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
+        ("array_to_object", |args: Vec<Expression>| {
+            if args.len() == 2 {
+                if let (Expression::Array(keys), Expression::Array(values)) = (&args[0], &args[1]) {
+                    // Pairs stop at the shorter array.
+                    let mut properties = ObjectMap::new();
+                    for (key, value) in keys.iter().zip(values) {
+                        let Expression::StringLiteral(key) = key else {
+                            return None;
+                        };
+                        properties.insert(key.clone(), value.clone());
+                    }
+                    Some(Expression::Object(properties))
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
+        ("object_freeze", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                if let Expression::Object(properties) | Expression::FrozenObject(properties) =
+                    &args[0]
+                {
+                    Some(Expression::FrozenObject(properties.clone()))
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
+        ("object_from_entries", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                if let Expression::Array(entries) = &args[0] {
+                    let mut properties = ObjectMap::new();
+
+                    for entry in entries {
+                        if let Expression::Array(pair) = entry {
+                            if let [Expression::StringLiteral(key), value] = pair.as_slice() {
+                                properties.insert(key.clone(), value.clone());
+                                continue;
+                            }
+                        }
+                        return None;
+                    }
+
+                    Some(Expression::Object(properties))
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        }),
     ]
 }
 
 fn object_to_string_impl(expr: &Expression) -> String {
     match expr {
         Expression::Number(n) => n.to_string(),
+        Expression::Float(n) => format_float(*n),
         Expression::Boolean(b) => b.to_string(),
         Expression::StringLiteral(s) => format!("\"{}\"", s),
         Expression::Undefined => "undefined".to_string(),
@@ -107,18 +220,20 @@ fn object_to_string_impl(expr: &Expression) -> String {
             let elements: Vec<String> = arr.iter().map(object_to_string_impl).collect();
             format!("[{}]", elements.join(", "))
         }
-        Expression::Object(properties) => {
+        Expression::Object(properties) | Expression::FrozenObject(properties) => {
             let elements: Vec<String> = properties
                 .iter()
                 .map(|(key, value)| format!("\"{}\": {}", key, object_to_string_impl(value)))
                 .collect();
             format!("{{{}}}", elements.join(", "))
         }
+        Expression::Map(_) => "\"<map>\"".to_string(),
         Expression::Variable(_) => "\"<variable>\"".to_string(),
         Expression::FunctionCall { .. } => "\"<function call>\"".to_string(),
         Expression::Comparison { .. } => "\"<comparison>\"".to_string(),
         Expression::BinaryOperation { .. } => "\"<binary operation>\"".to_string(),
         Expression::UnaryOperation { .. } => "\"<unary operation>\"".to_string(),
+        Expression::Conditional { .. } => "\"<conditional>\"".to_string(),
         Expression::PropertyAccess { .. } => "\"<property access>\"".to_string(),
         Expression::StringInterpolation { .. } => "\"<string interpolation>\"".to_string(),
     }
@@ -127,7 +242,7 @@ fn object_to_string_impl(expr: &Expression) -> String {
 #[cfg(test)]
 mod tests {
     use super::object_functions;
-    use crate::parser::ast::Expression;
+    use crate::parser::ast::{Expression, ObjectMap};
 
     fn object_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
         object_functions()
@@ -191,4 +306,130 @@ mod tests {
             Some(Expression::Array(items)) if items.len() == 2
         ));
     }
+
+    #[test]
+    fn from_entries_round_trips_object_entries() {
+        let create = object_fn("object_create");
+        let entries = object_fn("object_entries");
+        let from_entries = object_fn("object_from_entries");
+
+        let obj = create(vec![
+            Expression::StringLiteral("a".to_string()),
+            Expression::Number(1),
+            Expression::StringLiteral("b".to_string()),
+            Expression::StringLiteral("two".to_string()),
+        ])
+        .expect("object_create should return object");
+
+        let pairs = entries(vec![obj]).expect("object_entries should return array");
+        assert!(matches!(&pairs, Expression::Array(items) if items.len() == 2));
+
+        let Some(Expression::Object(rebuilt)) = from_entries(vec![pairs]) else {
+            panic!("object_from_entries should return object");
+        };
+        assert_eq!(rebuilt.len(), 2);
+        assert!(matches!(rebuilt.get("a"), Some(Expression::Number(1))));
+        assert!(matches!(
+            rebuilt.get("b"),
+            Some(Expression::StringLiteral(value)) if value == "two"
+        ));
+    }
+
+    #[test]
+    fn from_entries_rejects_malformed_pairs() {
+        let from_entries = object_fn("object_from_entries");
+
+        let non_string_key = Expression::Array(vec![Expression::Array(vec![
+            Expression::Number(1),
+            Expression::Number(2),
+        ])]);
+        let short_pair =
+            Expression::Array(vec![Expression::Array(vec![Expression::StringLiteral(
+                "a".to_string(),
+            )])]);
+        let not_a_pair = Expression::Array(vec![Expression::Number(1)]);
+
+        assert!(from_entries(vec![non_string_key]).is_none());
+        assert!(from_entries(vec![short_pair]).is_none());
+        assert!(from_entries(vec![not_a_pair]).is_none());
+    }
+
+    fn strings(values: &[&str]) -> Expression {
+        Expression::Array(
+            values
+                .iter()
+                .map(|value| Expression::StringLiteral(value.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn array_to_object_zips_keys_with_values() {
+        let array_to_object = object_fn("array_to_object");
+
+        let result = array_to_object(vec![
+            strings(&["a", "b"]),
+            Expression::Array(vec![Expression::Number(1), Expression::Boolean(true)]),
+        ]);
+        let Some(Expression::Object(properties)) = result else {
+            panic!("Expected an object");
+        };
+        assert_eq!(properties.keys().collect::<Vec<_>>(), ["a", "b"]);
+        assert!(matches!(properties.get("a"), Some(Expression::Number(1))));
+        assert!(matches!(
+            properties.get("b"),
+            Some(Expression::Boolean(true))
+        ));
+
+        assert!(matches!(
+            array_to_object(vec![
+                strings(&["a", "b", "c"]),
+                Expression::Array(vec![Expression::Number(1)]),
+            ]),
+            Some(Expression::Object(properties)) if properties.len() == 1
+        ));
+    }
+
+    #[test]
+    fn array_to_object_rejects_non_string_keys() {
+        let array_to_object = object_fn("array_to_object");
+
+        assert!(array_to_object(vec![
+            Expression::Array(vec![Expression::Number(1)]),
+            Expression::Array(vec![Expression::Number(2)]),
+        ])
+        .is_none());
+    }
+
+    #[test]
+    fn object_size_and_is_empty_count_properties() {
+        let size = object_fn("object_size");
+        let is_empty = object_fn("object_is_empty");
+
+        let empty = Expression::Object(ObjectMap::new());
+        assert!(matches!(
+            size(vec![empty.clone()]),
+            Some(Expression::Number(0))
+        ));
+        assert!(matches!(
+            is_empty(vec![empty]),
+            Some(Expression::Boolean(true))
+        ));
+
+        let mut properties = ObjectMap::new();
+        properties.insert("a".to_string(), Expression::Number(1));
+        properties.insert("b".to_string(), Expression::Null);
+        let populated = Expression::Object(properties);
+        assert!(matches!(
+            size(vec![populated.clone()]),
+            Some(Expression::Number(2))
+        ));
+        assert!(matches!(
+            is_empty(vec![populated]),
+            Some(Expression::Boolean(false))
+        ));
+
+        assert!(size(vec![Expression::Array(Vec::new())]).is_none());
+        assert!(is_empty(vec![Expression::Number(0)]).is_none());
+    }
 }
@@ -0,0 +1,87 @@
+use super::NativeFunctionEntry;
+use crate::parser::ast::Expression;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::sync::Mutex;
+
+lazy_static::lazy_static! {
+    static ref RNG: Mutex<StdRng> = Mutex::new(StdRng::from_entropy());
+}
+
+pub fn random_functions() -> Vec<NativeFunctionEntry> {
+    vec![
+        ("random_seed", random_seed),
+        ("random_choice", random_choice),
+    ]
+}
+
+/// Runs `f` with the process-wide RNG, which `random_seed` makes deterministic.
+pub(crate) fn with_rng<T>(f: impl FnOnce(&mut StdRng) -> T) -> T {
+    let mut rng = RNG.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut rng)
+}
+
+fn random_seed(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 1 {
+        return None;
+    }
+    if let Expression::Number(seed) = &args[0] {
+        with_rng(|rng| *rng = StdRng::seed_from_u64(i64::from(*seed) as u64));
+        Some(Expression::Null)
+    } else {
+        None
+    }
+}
+
+fn random_choice(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 1 {
+        return None;
+    }
+    if let Expression::Array(arr) = &args[0] {
+        Some(with_rng(|rng| arr.choose(rng).cloned()).unwrap_or(Expression::Null))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::random_functions;
+    use crate::parser::ast::Expression;
+
+    fn random_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
+        random_functions()
+            .into_iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, f)| f)
+            .expect("missing random function")
+    }
+
+    #[test]
+    fn choice_returns_elements_from_the_array() {
+        let choice = random_fn("random_choice");
+
+        assert!(matches!(
+            choice(vec![Expression::Array(vec![Expression::Number(7)])]),
+            Some(Expression::Number(7))
+        ));
+        assert!(matches!(
+            choice(vec![Expression::Array(vec![])]),
+            Some(Expression::Null)
+        ));
+
+        let values = Expression::Array(vec![
+            Expression::Number(1),
+            Expression::Number(2),
+            Expression::Number(3),
+        ]);
+        for _ in 0..100 {
+            assert!(matches!(
+                choice(vec![values.clone()]),
+                Some(Expression::Number(1..=3))
+            ));
+        }
+        assert!(choice(vec![Expression::Number(1)]).is_none());
+    }
+}
@@ -0,0 +1,90 @@
+use super::{fail, NativeFunctionEntry};
+use crate::parser::ast::Expression;
+use ::regex::Regex;
+
+pub fn regex_functions() -> Vec<NativeFunctionEntry> {
+    vec![("string_matches", string_matches)]
+}
+
+/// Returns `[full_match, group1, ...]` for the first match, or `null` when the
+/// pattern does not match. Groups that did not take part in the match are `null`.
+fn string_matches(args: Vec<Expression>) -> Option<Expression> {
+    let (text, pattern) = match args.as_slice() {
+        [Expression::StringLiteral(text), Expression::StringLiteral(pattern)] => (text, pattern),
+        _ => return None,
+    };
+    let regex = match Regex::new(pattern) {
+        Ok(regex) => regex,
+        Err(error) => return fail(format!("invalid pattern '{}': {}", pattern, error)),
+    };
+
+    Some(match regex.captures(text) {
+        Some(captures) => Expression::Array(
+            captures
+                .iter()
+                .map(|group| match group {
+                    Some(group) => Expression::StringLiteral(group.as_str().to_string()),
+                    None => Expression::Null,
+                })
+                .collect(),
+        ),
+        None => Expression::Null,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::regex_functions;
+    use crate::parser::ast::Expression;
+
+    fn regex_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
+        regex_functions()
+            .into_iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, f)| f)
+            .expect("missing regex function")
+    }
+
+    fn strings(values: &[&str]) -> Vec<Expression> {
+        values
+            .iter()
+            .map(|value| Expression::StringLiteral(value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn string_matches_returns_full_match_and_groups() {
+        let string_matches = regex_fn("string_matches");
+
+        let result = string_matches(strings(&[
+            "released on 2024-03-19.",
+            r"(\d{4})-(\d{2})-(\d{2})",
+        ]));
+        assert!(matches!(
+            result,
+            Some(Expression::Array(groups))
+                if groups.len() == 4
+                    && matches!(&groups[0], Expression::StringLiteral(s) if s == "2024-03-19")
+                    && matches!(&groups[1], Expression::StringLiteral(s) if s == "2024")
+                    && matches!(&groups[2], Expression::StringLiteral(s) if s == "03")
+                    && matches!(&groups[3], Expression::StringLiteral(s) if s == "19")
+        ));
+    }
+
+    #[test]
+    fn string_matches_handles_no_match_optional_groups_and_bad_patterns() {
+        let string_matches = regex_fn("string_matches");
+
+        assert!(matches!(
+            string_matches(strings(&["no dates here", r"\d{4}"])),
+            Some(Expression::Null)
+        ));
+        assert!(matches!(
+            string_matches(strings(&["ab", "a(x)?b"])),
+            Some(Expression::Array(groups))
+                if matches!(groups[1], Expression::Null)
+        ));
+        assert!(string_matches(strings(&["text", "("])).is_none());
+        assert!(string_matches(vec![Expression::Number(1)]).is_none());
+    }
+}
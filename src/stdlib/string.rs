@@ -1,5 +1,107 @@
-use super::NativeFunctionEntry;
-use crate::parser::ast::Expression;
+use super::math::round_to;
+use super::{fail, NativeFunctionEntry};
+use crate::parser::ast::{format_float, Expression, ObjectMap};
+
+/// Resolves a Python-style slice index against `len`, clamping to `0..=len`.
+fn slice_index(index: i32, len: usize) -> usize {
+    if index < 0 {
+        len.saturating_sub(index.unsigned_abs() as usize)
+    } else {
+        (index as usize).min(len)
+    }
+}
+
+/// Formats `value` with `decimals` fixed decimal places and commas between
+/// groups of thousands in the integer part. Halves round away from zero, as in
+/// `math_round_to`; `format!` alone would round them to even.
+fn format_grouped(value: f64, decimals: usize) -> String {
+    let rounded = round_to(value, i32::try_from(decimals).unwrap_or(i32::MAX));
+    let formatted = format!("{:.*}", decimals, rounded.abs());
+    let (integer, fraction) = match formatted.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (formatted.as_str(), None),
+    };
+
+    let mut grouped = String::new();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+
+    // Values that round to zero should not keep a negative sign.
+    if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        grouped.insert(0, '-');
+    }
+    grouped
+}
+
+/// Printed form of a scalar. Containers and undefined have no obvious string
+/// form and yield `None`.
+pub(super) fn scalar_text(value: &Expression) -> Option<String> {
+    match value {
+        Expression::StringLiteral(s) => Some(s.clone()),
+        Expression::Number(n) => Some(n.to_string()),
+        Expression::Float(n) => Some(format_float(*n)),
+        Expression::Boolean(b) => Some(b.to_string()),
+        Expression::Null => Some("null".to_string()),
+        _ => None,
+    }
+}
+
+/// Replaces `{{key}}` placeholders with the matching property of `context`.
+/// Unknown keys render as an empty string; an unclosed `{{` is kept as text.
+fn render_template(template: &str, context: &ObjectMap) -> Option<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        let key = rest[start + 2..start + 2 + length].trim();
+        if let Some(value) = context.get(key) {
+            rendered.push_str(&scalar_text(value)?);
+        }
+        rest = &rest[start + 2 + length + 2..];
+    }
+    rendered.push_str(rest);
+    Some(rendered)
+}
+
+/// Whether `args` is a single non-empty string whose characters all satisfy
+/// `predicate`.
+fn all_chars(args: &[Expression], predicate: fn(char) -> bool) -> Option<Expression> {
+    match args {
+        [Expression::StringLiteral(s)] => Some(Expression::Boolean(
+            !s.is_empty() && s.chars().all(predicate),
+        )),
+        _ => None,
+    }
+}
+
+/// Formats `n` in `radix` (2-36) with lowercase digits and a leading `-` for
+/// negative numbers.
+fn format_radix(n: i32, radix: u32) -> String {
+    let mut magnitude = n.unsigned_abs();
+    let mut digits = Vec::new();
+    loop {
+        digits.push(std::char::from_digit(magnitude % radix, radix).unwrap());
+        magnitude /= radix;
+        if magnitude == 0 {
+            break;
+        }
+    }
+    if n < 0 {
+        digits.push('-');
+    }
+    digits.iter().rev().collect()
+}
 
 pub fn string_functions() -> Vec<NativeFunctionEntry> {
     vec![
@@ -18,11 +120,8 @@ pub fn string_functions() -> Vec<NativeFunctionEntry> {
         ("string_concat", |args: Vec<Expression>| {
             if args.len() >= 2 {
                 let mut result = String::new();
-                for arg in args {
-                    match arg {
-                        Expression::StringLiteral(s) => result.push_str(&s),
-                        _ => return None,
-                    }
+                for arg in &args {
+                    result.push_str(&scalar_text(arg)?);
                 }
                 Some(Expression::StringLiteral(result))
             } else {
@@ -33,6 +132,7 @@ pub fn string_functions() -> Vec<NativeFunctionEntry> {
             if args.len() == 1 {
                 match &args[0] {
                     Expression::Number(n) => Some(Expression::StringLiteral(n.to_string())),
+                    Expression::Float(n) => Some(Expression::StringLiteral(format_float(*n))),
                     _ => None,
                 }
             } else {
@@ -42,7 +142,98 @@ pub fn string_functions() -> Vec<NativeFunctionEntry> {
         ("number_from_string", |args: Vec<Expression>| {
             if args.len() == 1 {
                 match &args[0] {
-                    Expression::StringLiteral(s) => s.parse::<i32>().ok().map(Expression::Number),
+                    Expression::StringLiteral(s) => match s.parse::<i32>() {
+                        Ok(number) => Some(Expression::Number(number)),
+                        Err(_) => fail(format!("'{}' is not a valid integer", s)),
+                    },
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        }),
+        ("parse_int", |args: Vec<Expression>| {
+            if args.len() == 2 {
+                match (&args[0], &args[1]) {
+                    (Expression::StringLiteral(s), Expression::Number(radix))
+                        if (2..=36).contains(radix) =>
+                    {
+                        Some(
+                            i32::from_str_radix(s, *radix as u32)
+                                .map(Expression::Number)
+                                .unwrap_or(Expression::Null),
+                        )
+                    }
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        }),
+        ("number_to_string", |args: Vec<Expression>| {
+            if args.len() == 2 {
+                match (&args[0], &args[1]) {
+                    (Expression::Number(n), Expression::Number(radix))
+                        if (2..=36).contains(radix) =>
+                    {
+                        Some(Expression::StringLiteral(format_radix(*n, *radix as u32)))
+                    }
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        }),
+        ("string_pad_number", |args: Vec<Expression>| {
+            if args.len() == 2 {
+                match (&args[0], &args[1]) {
+                    (Expression::Number(n), Expression::Number(width)) if *width >= 0 => {
+                        // The width counts digits only, so the sign goes in front of
+                        // the padded magnitude.
+                        let sign = if *n < 0 { "-" } else { "" };
+                        Some(Expression::StringLiteral(format!(
+                            "{}{:0width$}",
+                            sign,
+                            n.unsigned_abs(),
+                            width = *width as usize
+                        )))
+                    }
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        }),
+        ("string_slice", |args: Vec<Expression>| {
+            if !(2..=3).contains(&args.len()) {
+                return None;
+            }
+            let (Expression::StringLiteral(s), Expression::Number(start)) = (&args[0], &args[1])
+            else {
+                return None;
+            };
+            let chars: Vec<char> = s.chars().collect();
+            let start = slice_index(*start, chars.len());
+            // A missing or null end slices to the end of the string.
+            let end = match args.get(2) {
+                None | Some(Expression::Null) => chars.len(),
+                Some(Expression::Number(end)) => slice_index(*end, chars.len()),
+                _ => return None,
+            };
+            let slice: String = chars[start..end.max(start)].iter().collect();
+            Some(Expression::StringLiteral(slice))
+        }),
+        ("number_format", |args: Vec<Expression>| {
+            if args.len() == 2 {
+                let value = match &args[0] {
+                    Expression::Number(n) => *n as f64,
+                    Expression::Float(n) if n.is_finite() => *n,
+                    _ => return None,
+                };
+                match &args[1] {
+                    Expression::Number(decimals) if *decimals >= 0 => Some(
+                        Expression::StringLiteral(format_grouped(value, *decimals as usize)),
+                    ),
                     _ => None,
                 }
             } else {
@@ -187,13 +378,68 @@ pub fn string_functions() -> Vec<NativeFunctionEntry> {
                 None
             }
         }),
+        ("string_to_chars", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                match &args[0] {
+                    Expression::StringLiteral(s) => Some(Expression::Array(
+                        s.chars()
+                            .map(|c| Expression::StringLiteral(c.to_string()))
+                            .collect(),
+                    )),
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        }),
+        ("chars_to_string", |args: Vec<Expression>| {
+            if args.len() == 1 {
+                match &args[0] {
+                    Expression::Array(chars) => chars
+                        .iter()
+                        .map(|c| match c {
+                            Expression::StringLiteral(c) => Some(c.as_str()),
+                            _ => None,
+                        })
+                        .collect::<Option<String>>()
+                        .map(Expression::StringLiteral),
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        }),
+        ("string_is_numeric", |args: Vec<Expression>| {
+            // Only ASCII digits, the ones `number_from_string` can parse; Unicode
+            // numerals such as "½" or "Ⅻ" are not numeric here.
+            all_chars(&args, |c| c.is_ascii_digit())
+        }),
+        ("string_is_alpha", |args: Vec<Expression>| {
+            all_chars(&args, char::is_alphabetic)
+        }),
+        ("string_is_alphanumeric", |args: Vec<Expression>| {
+            all_chars(&args, char::is_alphanumeric)
+        }),
+        ("template_render", |args: Vec<Expression>| {
+            if args.len() == 2 {
+                match (&args[0], &args[1]) {
+                    (
+                        Expression::StringLiteral(template),
+                        Expression::Object(context) | Expression::FrozenObject(context),
+                    ) => render_template(template, context).map(Expression::StringLiteral),
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        }),
     ]
 }
 
 #[cfg(test)]
 mod tests {
     use super::string_functions;
-    use crate::parser::ast::Expression;
+    use crate::parser::ast::{Expression, ObjectMap};
 
     #[test]
     fn string_substring_handles_utf8_without_panicking() {
@@ -215,6 +461,107 @@ mod tests {
         }
     }
 
+    #[test]
+    fn string_from_number_formats_integers_and_floats() {
+        let func = string_functions()
+            .into_iter()
+            .find(|(name, _)| *name == "string_from_number")
+            .map(|(_, f)| f)
+            .expect("missing string_from_number function");
+
+        for (number, expected) in [
+            (Expression::Number(42), "42"),
+            (Expression::Float(1.5), "1.5"),
+            (Expression::Float(3.0), "3.0"),
+        ] {
+            match func(vec![number]) {
+                Some(Expression::StringLiteral(value)) => assert_eq!(value, expected),
+                other => panic!("Expected a string, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn parse_int_handles_radixes_and_invalid_digits() {
+        let func = string_functions()
+            .into_iter()
+            .find(|(name, _)| *name == "parse_int")
+            .map(|(_, f)| f)
+            .expect("missing parse_int function");
+
+        assert!(matches!(
+            func(vec![
+                Expression::StringLiteral("ff".to_string()),
+                Expression::Number(16)
+            ]),
+            Some(Expression::Number(255))
+        ));
+        assert!(matches!(
+            func(vec![
+                Expression::StringLiteral("101".to_string()),
+                Expression::Number(2)
+            ]),
+            Some(Expression::Number(5))
+        ));
+        assert!(matches!(
+            func(vec![
+                Expression::StringLiteral("102".to_string()),
+                Expression::Number(2)
+            ]),
+            Some(Expression::Null)
+        ));
+        assert!(func(vec![
+            Expression::StringLiteral("1".to_string()),
+            Expression::Number(37)
+        ])
+        .is_none());
+    }
+
+    #[test]
+    fn number_to_string_formats_in_the_given_radix() {
+        let func = string_functions()
+            .into_iter()
+            .find(|(name, _)| *name == "number_to_string")
+            .map(|(_, f)| f)
+            .expect("missing number_to_string function");
+        let format =
+            |n: i32, radix: i32| match func(vec![Expression::Number(n), Expression::Number(radix)])
+            {
+                Some(Expression::StringLiteral(value)) => Some(value),
+                _ => None,
+            };
+
+        assert_eq!(format(255, 16).as_deref(), Some("ff"));
+        assert_eq!(format(5, 2).as_deref(), Some("101"));
+        assert_eq!(format(0, 2).as_deref(), Some("0"));
+        assert_eq!(format(-255, 16).as_deref(), Some("-ff"));
+        assert_eq!(format(35, 36).as_deref(), Some("z"));
+        assert_eq!(format(i32::MIN, 16).as_deref(), Some("-80000000"));
+        assert_eq!(format(10, 1), None);
+        assert_eq!(format(10, 37), None);
+    }
+
+    #[test]
+    fn string_pad_number_zero_pads_digits_and_keeps_the_sign() {
+        let func = string_functions()
+            .into_iter()
+            .find(|(name, _)| *name == "string_pad_number")
+            .map(|(_, f)| f)
+            .expect("missing string_pad_number function");
+        let pad =
+            |n: i32, width: i32| match func(vec![Expression::Number(n), Expression::Number(width)])
+            {
+                Some(Expression::StringLiteral(value)) => Some(value),
+                _ => None,
+            };
+
+        assert_eq!(pad(7, 3).as_deref(), Some("007"));
+        assert_eq!(pad(12345, 3).as_deref(), Some("12345"));
+        assert_eq!(pad(-42, 4).as_deref(), Some("-0042"));
+        assert_eq!(pad(0, 0).as_deref(), Some("0"));
+        assert_eq!(pad(1, -1), None);
+    }
+
     #[test]
     fn string_split_returns_array_of_segments() {
         let func = string_functions()
@@ -238,4 +585,230 @@ mod tests {
             _ => panic!("Expected string_split array result"),
         }
     }
+
+    #[test]
+    fn string_concat_coerces_scalars_and_rejects_containers() {
+        let func = string_functions()
+            .into_iter()
+            .find(|(name, _)| *name == "string_concat")
+            .map(|(_, f)| f)
+            .expect("missing string_concat function");
+
+        assert!(matches!(
+            func(vec![
+                Expression::StringLiteral("count: ".to_string()),
+                Expression::Number(3)
+            ]),
+            Some(Expression::StringLiteral(value)) if value == "count: 3"
+        ));
+        assert!(matches!(
+            func(vec![
+                Expression::StringLiteral("ok=".to_string()),
+                Expression::Boolean(true)
+            ]),
+            Some(Expression::StringLiteral(value)) if value == "ok=true"
+        ));
+        assert!(func(vec![
+            Expression::StringLiteral("items: ".to_string()),
+            Expression::Array(vec![Expression::Number(1)])
+        ])
+        .is_none());
+        assert!(func(vec![
+            Expression::StringLiteral("obj: ".to_string()),
+            Expression::Object(ObjectMap::new())
+        ])
+        .is_none());
+    }
+
+    #[test]
+    fn string_slice_supports_negative_and_out_of_range_indices() {
+        let func = string_functions()
+            .into_iter()
+            .find(|(name, _)| *name == "string_slice")
+            .map(|(_, f)| f)
+            .expect("missing string_slice function");
+
+        assert!(matches!(
+            func(vec![
+                Expression::StringLiteral("héllo".to_string()),
+                Expression::Number(1),
+                Expression::Number(3)
+            ]),
+            Some(Expression::StringLiteral(value)) if value == "él"
+        ));
+        assert!(matches!(
+            func(vec![
+                Expression::StringLiteral("vexel.vx".to_string()),
+                Expression::Number(-2)
+            ]),
+            Some(Expression::StringLiteral(value)) if value == "vx"
+        ));
+        assert!(matches!(
+            func(vec![
+                Expression::StringLiteral("abc".to_string()),
+                Expression::Number(-10),
+                Expression::Number(10)
+            ]),
+            Some(Expression::StringLiteral(value)) if value == "abc"
+        ));
+        assert!(matches!(
+            func(vec![
+                Expression::StringLiteral("abc".to_string()),
+                Expression::Number(2),
+                Expression::Number(1)
+            ]),
+            Some(Expression::StringLiteral(value)) if value.is_empty()
+        ));
+    }
+
+    #[test]
+    fn number_format_groups_thousands_and_rounds_decimals() {
+        let func = string_functions()
+            .into_iter()
+            .find(|(name, _)| *name == "number_format")
+            .map(|(_, f)| f)
+            .expect("missing number_format function");
+
+        assert!(matches!(
+            func(vec![Expression::Number(1234567), Expression::Number(0)]),
+            Some(Expression::StringLiteral(value)) if value == "1,234,567"
+        ));
+        assert!(matches!(
+            func(vec![Expression::Number(-1234), Expression::Number(0)]),
+            Some(Expression::StringLiteral(value)) if value == "-1,234"
+        ));
+        assert!(matches!(
+            func(vec![Expression::Float(1234.5678), Expression::Number(2)]),
+            Some(Expression::StringLiteral(value)) if value == "1,234.57"
+        ));
+        assert!(matches!(
+            func(vec![Expression::Float(-0.001), Expression::Number(1)]),
+            Some(Expression::StringLiteral(value)) if value == "0.0"
+        ));
+        assert!(func(vec![Expression::Number(1), Expression::Number(-1)]).is_none());
+    }
+
+    #[test]
+    fn number_format_rounds_halves_away_from_zero() {
+        let func = string_functions()
+            .into_iter()
+            .find(|(name, _)| *name == "number_format")
+            .map(|(_, f)| f)
+            .expect("missing number_format function");
+
+        for (value, decimals, expected) in [
+            (2.5, 0, "3"),
+            (1234.5, 0, "1,235"),
+            (0.125, 2, "0.13"),
+            (-2.5, 0, "-3"),
+        ] {
+            assert!(matches!(
+                func(vec![Expression::Float(value), Expression::Number(decimals)]),
+                Some(Expression::StringLiteral(text)) if text == expected
+            ));
+        }
+    }
+
+    #[test]
+    fn template_render_fills_known_keys_and_blanks_missing_ones() {
+        let func = string_functions()
+            .into_iter()
+            .find(|(name, _)| *name == "template_render")
+            .map(|(_, f)| f)
+            .expect("missing template_render function");
+
+        let mut context = ObjectMap::new();
+        context.insert(
+            "name".to_string(),
+            Expression::StringLiteral("Ada".to_string()),
+        );
+        context.insert("count".to_string(), Expression::Number(3));
+
+        let render = |template: &str| {
+            func(vec![
+                Expression::StringLiteral(template.to_string()),
+                Expression::Object(context.clone()),
+            ])
+        };
+
+        assert!(matches!(
+            render("Hi {{name}}, you have {{ count }} messages"),
+            Some(Expression::StringLiteral(value)) if value == "Hi Ada, you have 3 messages"
+        ));
+        assert!(matches!(
+            render("[{{missing}}] {{name"),
+            Some(Expression::StringLiteral(value)) if value == "[] {{name"
+        ));
+
+        context.insert("nested".to_string(), Expression::Array(Vec::new()));
+        assert!(func(vec![
+            Expression::StringLiteral("{{nested}}".to_string()),
+            Expression::Object(context.clone()),
+        ])
+        .is_none());
+    }
+
+    #[test]
+    fn string_to_chars_round_trips_multibyte_strings() {
+        let to_chars = string_functions()
+            .into_iter()
+            .find(|(name, _)| *name == "string_to_chars")
+            .map(|(_, f)| f)
+            .expect("missing string_to_chars function");
+        let from_chars = string_functions()
+            .into_iter()
+            .find(|(name, _)| *name == "chars_to_string")
+            .map(|(_, f)| f)
+            .expect("missing chars_to_string function");
+
+        let text = "héllo, 世界";
+        let chars = match to_chars(vec![Expression::StringLiteral(text.to_string())]) {
+            Some(Expression::Array(chars)) => chars,
+            _ => panic!("Expected an array of characters"),
+        };
+        assert_eq!(chars.len(), text.chars().count());
+        assert!(matches!(&chars[1], Expression::StringLiteral(c) if c == "é"));
+
+        assert!(matches!(
+            from_chars(vec![Expression::Array(chars)]),
+            Some(Expression::StringLiteral(value)) if value == text
+        ));
+        assert!(from_chars(vec![Expression::Array(vec![Expression::Number(1)])]).is_none());
+    }
+
+    #[test]
+    fn string_predicates_check_every_character() {
+        let lookup = |wanted: &str| {
+            string_functions()
+                .into_iter()
+                .find(|(name, _)| *name == wanted)
+                .map(|(_, f)| f)
+                .expect("missing string predicate")
+        };
+        let check = |name: &str, input: &str| match lookup(name)(vec![Expression::StringLiteral(
+            input.to_string(),
+        )]) {
+            Some(Expression::Boolean(value)) => value,
+            _ => panic!("Expected a boolean from {}", name),
+        };
+
+        assert!(check("string_is_numeric", "12345"));
+        assert!(!check("string_is_numeric", "12.5"));
+        assert!(!check("string_is_numeric", "abc"));
+        assert!(!check("string_is_numeric", "½"));
+        assert!(!check("string_is_numeric", "Ⅻ"));
+        assert!(!check("string_is_numeric", "١٢٣"));
+        assert!(check("string_is_alpha", "héllo"));
+        assert!(!check("string_is_alpha", "abc1"));
+        assert!(check("string_is_alphanumeric", "abc123"));
+        assert!(!check("string_is_alphanumeric", "abc 123"));
+        for name in [
+            "string_is_numeric",
+            "string_is_alpha",
+            "string_is_alphanumeric",
+        ] {
+            assert!(!check(name, ""));
+        }
+        assert!(lookup("string_is_alpha")(vec![Expression::Number(1)]).is_none());
+    }
 }
@@ -1,23 +1,25 @@
 use super::NativeFunctionEntry;
 use crate::parser::ast::Expression;
 use rustc_hash::FxHashMap as HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::thread;
+use std::time::Duration;
 
 lazy_static::lazy_static! {
     static ref CHANNEL_SENDERS: Mutex<HashMap<String, Sender<Expression>>> = Mutex::new(HashMap::default());
     static ref CHANNEL_RECEIVERS: Mutex<HashMap<String, Receiver<Expression>>> = Mutex::new(HashMap::default());
+    static ref ATOMICS: Mutex<HashMap<String, i32>> = Mutex::new(HashMap::default());
 }
 
+static NEXT_ATOMIC_ID: AtomicUsize = AtomicUsize::new(1);
+static NEXT_CHANNEL_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Channel ids come from a counter; timestamps can repeat when two channels are
+/// created back to back, and a repeated id would replace the earlier channel.
 fn next_channel_id() -> String {
-    format!(
-        "ch{}",
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_nanos())
-            .unwrap_or(0)
-    )
+    format!("ch{}", NEXT_CHANNEL_ID.fetch_add(1, Ordering::Relaxed))
 }
 
 fn thread_channel(args: Vec<Expression>) -> Option<Expression> {
@@ -81,6 +83,81 @@ fn thread_close(args: Vec<Expression>) -> Option<Expression> {
     Some(Expression::Null)
 }
 
+fn atomic_new(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 1 {
+        return None;
+    }
+
+    let Expression::Number(initial) = &args[0] else {
+        return None;
+    };
+
+    let id = format!("atomic{}", NEXT_ATOMIC_ID.fetch_add(1, Ordering::Relaxed));
+    ATOMICS.lock().ok()?.insert(id.clone(), *initial);
+    Some(Expression::StringLiteral(id))
+}
+
+/// Adds `delta` and returns the new value. The read and write happen under one
+/// lock, so concurrent adds from several threads are never lost.
+fn atomic_add(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 2 {
+        return None;
+    }
+
+    let (Expression::StringLiteral(id), Expression::Number(delta)) = (&args[0], &args[1]) else {
+        return None;
+    };
+
+    let mut atomics = ATOMICS.lock().ok()?;
+    let value = atomics.get_mut(id)?;
+    *value = value.checked_add(*delta)?;
+    Some(Expression::Number(*value))
+}
+
+fn atomic_get(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 1 {
+        return None;
+    }
+
+    let Expression::StringLiteral(id) = &args[0] else {
+        return None;
+    };
+
+    ATOMICS
+        .lock()
+        .ok()?
+        .get(id)
+        .copied()
+        .map(Expression::Number)
+}
+
+/// Sleeps only the calling OS thread, so a spawned worker can pause without
+/// holding up the main script or other workers.
+fn thread_sleep_ms(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() != 1 {
+        return None;
+    }
+
+    match &args[0] {
+        Expression::Number(millis) if *millis >= 0 => {
+            thread::sleep(Duration::from_millis(*millis as u64));
+            Some(Expression::Null)
+        }
+        _ => None,
+    }
+}
+
+/// Lets the OS scheduler run another thread before the caller continues; useful
+/// in polling loops that would otherwise spin.
+fn thread_yield(args: Vec<Expression>) -> Option<Expression> {
+    if !args.is_empty() {
+        return None;
+    }
+
+    thread::yield_now();
+    Some(Expression::Null)
+}
+
 pub fn thread_functions() -> Vec<NativeFunctionEntry> {
     vec![
         (
@@ -99,6 +176,26 @@ pub fn thread_functions() -> Vec<NativeFunctionEntry> {
             "thread_close",
             thread_close as fn(Vec<Expression>) -> Option<Expression>,
         ),
+        (
+            "thread_sleep_ms",
+            thread_sleep_ms as fn(Vec<Expression>) -> Option<Expression>,
+        ),
+        (
+            "thread_yield",
+            thread_yield as fn(Vec<Expression>) -> Option<Expression>,
+        ),
+        (
+            "atomic_new",
+            atomic_new as fn(Vec<Expression>) -> Option<Expression>,
+        ),
+        (
+            "atomic_add",
+            atomic_add as fn(Vec<Expression>) -> Option<Expression>,
+        ),
+        (
+            "atomic_get",
+            atomic_get as fn(Vec<Expression>) -> Option<Expression>,
+        ),
     ]
 }
 
@@ -115,6 +212,14 @@ mod tests {
             .expect("missing thread function")
     }
 
+    #[test]
+    fn thread_yield_returns_null_and_takes_no_arguments() {
+        let thread_yield = thread_fn("thread_yield");
+
+        assert!(matches!(thread_yield(vec![]), Some(Expression::Null)));
+        assert!(thread_yield(vec![Expression::Number(1)]).is_none());
+    }
+
     #[test]
     fn channel_send_and_recv_roundtrip() {
         let channel = thread_fn("thread_channel");
@@ -137,6 +242,48 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn atomic_add_returns_the_updated_value() {
+        let new = thread_fn("atomic_new");
+        let add = thread_fn("atomic_add");
+        let get = thread_fn("atomic_get");
+
+        let Some(Expression::StringLiteral(id)) = new(vec![Expression::Number(5)]) else {
+            panic!("atomic_new should return an id");
+        };
+        assert!(matches!(
+            add(vec![
+                Expression::StringLiteral(id.clone()),
+                Expression::Number(-2)
+            ]),
+            Some(Expression::Number(3))
+        ));
+        assert!(matches!(
+            get(vec![Expression::StringLiteral(id)]),
+            Some(Expression::Number(3))
+        ));
+        assert!(get(vec![Expression::StringLiteral("missing".to_string())]).is_none());
+    }
+
+    #[test]
+    fn channels_created_back_to_back_get_distinct_ids() {
+        let channel = thread_fn("thread_channel");
+        let close = thread_fn("thread_close");
+
+        let ids: Vec<String> = (0..200)
+            .map(|_| match channel(vec![]) {
+                Some(Expression::StringLiteral(id)) => id,
+                other => panic!("thread_channel should return an id, got {:?}", other),
+            })
+            .collect();
+        let unique: std::collections::HashSet<&String> = ids.iter().collect();
+        assert_eq!(unique.len(), ids.len());
+
+        for id in ids {
+            assert!(close(vec![Expression::StringLiteral(id)]).is_some());
+        }
+    }
+
     #[test]
     fn recv_unknown_channel_returns_none() {
         let recv = thread_fn("thread_recv");
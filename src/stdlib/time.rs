@@ -0,0 +1,107 @@
+use super::NativeFunctionEntry;
+use crate::parser::ast::Expression;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn time_functions() -> Vec<NativeFunctionEntry> {
+    vec![("now_iso", now_iso)]
+}
+
+fn now_iso(args: Vec<Expression>) -> Option<Expression> {
+    if !args.is_empty() {
+        return None;
+    }
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(Expression::StringLiteral(format_iso(seconds)))
+}
+
+/// Formats seconds since the Unix epoch as `YYYY-MM-DDTHH:MM:SSZ` in UTC.
+fn format_iso(seconds: u64) -> String {
+    let days = (seconds / 86_400) as i64;
+    let time = seconds % 86_400;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
+
+/// Converts days since 1970-01-01 to a proleptic Gregorian (year, month, day),
+/// using Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_iso, time_functions};
+    use crate::parser::ast::Expression;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn time_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
+        time_functions()
+            .into_iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, f)| f)
+            .expect("missing time function")
+    }
+
+    /// Inverse of `civil_from_days`, used to parse timestamps back.
+    fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month_index = if month > 2 { month - 3 } else { month + 9 };
+        let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    #[test]
+    fn format_iso_handles_epoch_and_leap_days() {
+        assert_eq!(format_iso(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_iso(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_iso(1_704_164_645), "2024-01-02T03:04:05Z");
+    }
+
+    #[test]
+    fn now_iso_returns_the_current_utc_time() {
+        let Some(Expression::StringLiteral(stamp)) = time_fn("now_iso")(vec![]) else {
+            panic!("now_iso should return a string");
+        };
+        let pattern =
+            regex::Regex::new(r"^(\d{4})-(\d{2})-(\d{2})T(\d{2}):(\d{2}):(\d{2})Z$").unwrap();
+        let captures = pattern
+            .captures(&stamp)
+            .unwrap_or_else(|| panic!("'{}' is not an ISO-8601 timestamp", stamp));
+        let field = |index: usize| captures[index].parse::<i64>().unwrap();
+
+        let parsed = days_from_civil(field(1), field(2), field(3)) * 86_400
+            + field(4) * 3_600
+            + field(5) * 60
+            + field(6);
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        assert!((now - parsed).abs() <= 5, "{} is not close to now", stamp);
+    }
+}
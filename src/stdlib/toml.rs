@@ -0,0 +1,110 @@
+use super::{fail, NativeFunctionEntry};
+use crate::parser::ast::{Expression, ObjectMap};
+use ::toml::{Table, Value};
+
+pub fn toml_functions() -> Vec<NativeFunctionEntry> {
+    vec![("toml_parse", toml_parse)]
+}
+
+fn toml_parse(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() == 1 {
+        if let Expression::StringLiteral(text) = &args[0] {
+            match text.parse::<Table>() {
+                Ok(table) => Some(table_to_expression(table)),
+                Err(error) => fail(format!("invalid TOML: {}", error.message())),
+            }
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
+
+fn table_to_expression(table: Table) -> Expression {
+    let mut props = ObjectMap::new();
+    for (key, value) in table {
+        props.insert(key, value_to_expression(value));
+    }
+    Expression::Object(props)
+}
+
+fn value_to_expression(value: Value) -> Expression {
+    match value {
+        Value::String(s) => Expression::StringLiteral(s),
+        // Integers outside the i32 range fall back to floats, as in json_parse.
+        Value::Integer(i) => i32::try_from(i)
+            .map(Expression::Number)
+            .unwrap_or(Expression::Float(i as f64)),
+        Value::Float(f) => Expression::Float(f),
+        Value::Boolean(b) => Expression::Boolean(b),
+        Value::Datetime(datetime) => Expression::StringLiteral(datetime.to_string()),
+        Value::Array(items) => {
+            Expression::Array(items.into_iter().map(value_to_expression).collect())
+        }
+        Value::Table(table) => table_to_expression(table),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::toml_functions;
+    use crate::parser::ast::Expression;
+
+    fn toml_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
+        toml_functions()
+            .into_iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, f)| f)
+            .expect("missing toml function")
+    }
+
+    #[test]
+    fn parse_maps_sections_to_nested_objects() {
+        let parse = toml_fn("toml_parse");
+
+        let source = r#"
+name = "vexel"
+
+[package]
+version = "0.1.0"
+authors = ["a", "b"]
+
+[package.limits]
+threads = 4
+ratio = 0.5
+released = 1979-05-27
+"#;
+        let parsed = parse(vec![Expression::StringLiteral(source.to_string())])
+            .expect("toml_parse should return an object");
+
+        let Expression::Object(root) = &parsed else {
+            panic!("Expected an object");
+        };
+        assert!(matches!(root.get("name"), Some(Expression::StringLiteral(s)) if s == "vexel"));
+        let Some(Expression::Object(package)) = root.get("package") else {
+            panic!("Expected a package table");
+        };
+        assert!(matches!(
+            package.get("authors"),
+            Some(Expression::Array(authors)) if authors.len() == 2
+        ));
+        let Some(Expression::Object(limits)) = package.get("limits") else {
+            panic!("Expected a nested limits table");
+        };
+        assert!(matches!(limits.get("threads"), Some(Expression::Number(4))));
+        assert!(matches!(limits.get("ratio"), Some(Expression::Float(n)) if *n == 0.5));
+        assert!(matches!(
+            limits.get("released"),
+            Some(Expression::StringLiteral(s)) if s == "1979-05-27"
+        ));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_toml() {
+        let parse = toml_fn("toml_parse");
+
+        assert!(parse(vec![Expression::StringLiteral("key = ".to_string())]).is_none());
+        assert!(parse(vec![Expression::Null]).is_none());
+    }
+}
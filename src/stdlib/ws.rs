@@ -0,0 +1,178 @@
+use super::{fail, NativeFunctionEntry};
+use crate::parser::ast::Expression;
+use rustc_hash::FxHashMap as HashMap;
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Error, Message, WebSocket};
+
+type Socket = Arc<Mutex<WebSocket<MaybeTlsStream<TcpStream>>>>;
+
+lazy_static::lazy_static! {
+    static ref SOCKETS: Mutex<HashMap<String, Socket>> = Mutex::new(HashMap::default());
+}
+
+static NEXT_SOCKET_ID: AtomicUsize = AtomicUsize::new(1);
+
+pub fn ws_functions() -> Vec<NativeFunctionEntry> {
+    vec![
+        ("ws_connect", ws_connect),
+        ("ws_send", ws_send),
+        ("ws_recv", ws_recv),
+        ("ws_close", ws_close),
+    ]
+}
+
+/// Each socket has its own lock so a blocking `ws_recv` only holds up calls on
+/// the same socket, not every socket in the registry.
+fn socket(id: &str) -> Option<Socket> {
+    match SOCKETS.lock().ok()?.get(id) {
+        Some(socket) => Some(Arc::clone(socket)),
+        None => fail(format!("unknown socket '{}'", id)),
+    }
+}
+
+fn ws_connect(args: Vec<Expression>) -> Option<Expression> {
+    let [Expression::StringLiteral(url)] = args.as_slice() else {
+        return None;
+    };
+    let socket = match tungstenite::connect(url.as_str()) {
+        Ok((socket, _)) => socket,
+        Err(error) => return fail(format!("cannot connect to '{}': {}", url, error)),
+    };
+
+    let id = format!("ws{}", NEXT_SOCKET_ID.fetch_add(1, Ordering::Relaxed));
+    SOCKETS
+        .lock()
+        .ok()?
+        .insert(id.clone(), Arc::new(Mutex::new(socket)));
+    Some(Expression::StringLiteral(id))
+}
+
+fn ws_send(args: Vec<Expression>) -> Option<Expression> {
+    let [Expression::StringLiteral(id), Expression::StringLiteral(message)] = args.as_slice()
+    else {
+        return None;
+    };
+    let socket = socket(id)?;
+    let mut socket = socket.lock().ok()?;
+    match socket.send(Message::Text(message.clone())) {
+        Ok(()) => Some(Expression::Null),
+        Err(error) => fail(error.to_string()),
+    }
+}
+
+/// Blocks until the next text or binary message. Returns `null` once the peer
+/// closes the connection; control frames are handled internally.
+fn ws_recv(args: Vec<Expression>) -> Option<Expression> {
+    let [Expression::StringLiteral(id)] = args.as_slice() else {
+        return None;
+    };
+    let socket = socket(id)?;
+    let mut socket = socket.lock().ok()?;
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => return Some(Expression::StringLiteral(text)),
+            Ok(Message::Binary(bytes)) => {
+                return Some(Expression::StringLiteral(
+                    String::from_utf8_lossy(&bytes).into_owned(),
+                ))
+            }
+            Ok(Message::Close(_)) | Err(Error::ConnectionClosed | Error::AlreadyClosed) => {
+                return Some(Expression::Null)
+            }
+            Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_)) => continue,
+            Err(error) => return fail(error.to_string()),
+        }
+    }
+}
+
+fn ws_close(args: Vec<Expression>) -> Option<Expression> {
+    let [Expression::StringLiteral(id)] = args.as_slice() else {
+        return None;
+    };
+    let Some(socket) = SOCKETS.lock().ok()?.remove(id) else {
+        return fail(format!("unknown socket '{}'", id));
+    };
+    let mut socket = socket.lock().ok()?;
+    // The peer may already be gone; the handle is released either way.
+    let _ = socket.close(None);
+    let _ = socket.flush();
+    Some(Expression::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ws_functions;
+    use crate::parser::ast::Expression;
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn ws_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
+        ws_functions()
+            .into_iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, f)| f)
+            .expect("missing ws function")
+    }
+
+    /// Accepts one client and echoes its messages until it disconnects.
+    fn echo_server() -> (String, thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket = tungstenite::accept(stream).unwrap();
+            while let Ok(message) = socket.read() {
+                if message.is_text() || message.is_binary() {
+                    socket.send(message).unwrap();
+                }
+            }
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn sent_messages_are_echoed_back() {
+        let (url, server) = echo_server();
+        let connect = ws_fn("ws_connect");
+        let send = ws_fn("ws_send");
+        let recv = ws_fn("ws_recv");
+        let close = ws_fn("ws_close");
+
+        let handle = connect(vec![Expression::StringLiteral(url)]).expect("ws_connect failed");
+        for text in ["hello", "héllo again"] {
+            assert!(matches!(
+                send(vec![
+                    handle.clone(),
+                    Expression::StringLiteral(text.to_string())
+                ]),
+                Some(Expression::Null)
+            ));
+            assert!(matches!(
+                recv(vec![handle.clone()]),
+                Some(Expression::StringLiteral(echoed)) if echoed == text
+            ));
+        }
+
+        assert!(matches!(
+            close(vec![handle.clone()]),
+            Some(Expression::Null)
+        ));
+        server.join().unwrap();
+        assert!(close(vec![handle.clone()]).is_none());
+        assert!(recv(vec![handle]).is_none());
+    }
+
+    #[test]
+    fn connect_fails_for_unreachable_servers() {
+        let connect = ws_fn("ws_connect");
+
+        assert!(connect(vec![Expression::StringLiteral(
+            "ws://127.0.0.1:1/".to_string()
+        )])
+        .is_none());
+        assert!(connect(vec![Expression::Number(1)]).is_none());
+    }
+}
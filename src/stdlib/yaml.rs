@@ -0,0 +1,97 @@
+use super::json::{expression_to_value, value_to_expression};
+use super::{fail, NativeFunctionEntry};
+use crate::parser::ast::Expression;
+
+pub fn yaml_functions() -> Vec<NativeFunctionEntry> {
+    vec![
+        ("yaml_parse", yaml_parse),
+        ("yaml_stringify", yaml_stringify),
+    ]
+}
+
+// Documents go through serde_json values so YAML shares the JSON conversions:
+// mapping keys become strings and integers outside the i32 range become floats.
+
+fn yaml_parse(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() == 1 {
+        if let Expression::StringLiteral(yaml) = &args[0] {
+            match serde_yaml::from_str::<serde_json::Value>(yaml) {
+                Ok(value) => value_to_expression(&value),
+                Err(error) => fail(format!("invalid YAML: {}", error)),
+            }
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
+
+fn yaml_stringify(args: Vec<Expression>) -> Option<Expression> {
+    if args.len() == 1 {
+        let value = expression_to_value(&args[0])?;
+        serde_yaml::to_string(&value)
+            .ok()
+            .map(Expression::StringLiteral)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::yaml_functions;
+    use crate::parser::ast::Expression;
+
+    fn yaml_fn(name: &str) -> fn(Vec<Expression>) -> Option<Expression> {
+        yaml_functions()
+            .into_iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, f)| f)
+            .expect("missing yaml function")
+    }
+
+    #[test]
+    fn parse_and_stringify_round_trip_nested_mapping_with_list() {
+        let parse = yaml_fn("yaml_parse");
+        let stringify = yaml_fn("yaml_stringify");
+
+        let source = "server:\n  host: localhost\n  port: 8080\n  tags:\n  - web\n  - 1.5\n  - null\nenabled: true\n";
+        let parsed = parse(vec![Expression::StringLiteral(source.to_string())])
+            .expect("yaml_parse should return an object");
+
+        let Expression::Object(root) = &parsed else {
+            panic!("Expected an object");
+        };
+        let Some(Expression::Object(server)) = root.get("server") else {
+            panic!("Expected a nested server mapping");
+        };
+        assert!(matches!(server.get("port"), Some(Expression::Number(8080))));
+        assert!(matches!(
+            server.get("tags"),
+            Some(Expression::Array(tags)) if tags.len() == 3
+                && matches!(&tags[1], Expression::Float(n) if *n == 1.5)
+                && matches!(tags[2], Expression::Null)
+        ));
+        assert!(matches!(
+            root.get("enabled"),
+            Some(Expression::Boolean(true))
+        ));
+
+        assert!(matches!(
+            stringify(vec![parsed]),
+            Some(Expression::StringLiteral(yaml)) if yaml == source
+        ));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_yaml_and_non_string_input() {
+        let parse = yaml_fn("yaml_parse");
+
+        assert!(parse(vec![Expression::StringLiteral(
+            "key: [unclosed".to_string()
+        )])
+        .is_none());
+        assert!(parse(vec![Expression::Number(1)]).is_none());
+    }
+}
@@ -1,5 +1,8 @@
 mod common;
 
+use std::io::Write;
+use std::process::{Command, Stdio};
+
 use common::{
     create_workspace, run_script, run_vexel, stderr_text, stdout_text, write_workspace_file,
 };
@@ -49,6 +52,70 @@ fn reports_runtime_errors_with_non_zero_exit() {
     assert!(stderr_text(&output).contains("Undefined variable 'missing_value'"));
 }
 
+#[test]
+fn reports_call_stack_for_uncaught_errors_in_nested_functions() {
+    let workspace = create_workspace("runtime_error_stack");
+    let script = write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function inner(n) start
+    return n / 0
+end
+
+function outer(n) start
+    return inner(n) + 1
+end
+
+try start
+    print outer(1)
+catch err start
+    print err.message
+end
+print outer(2)
+"#,
+    );
+    let arg = script.to_string_lossy().to_string();
+
+    let output = run_vexel(&workspace, &[&arg]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout_text(&output),
+        "Division and modulo by zero are not allowed\n"
+    );
+    assert_eq!(
+        stderr_text(&output),
+        "line 3: Division and modulo by zero are not allowed\n  at inner\n  at outer\n"
+    );
+}
+
+#[test]
+fn reports_the_source_line_of_runtime_errors() {
+    let workspace = create_workspace("runtime_error_line");
+    let script = write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"# totals
+
+set items [1, 2, "three"]
+set total 0
+for item in items start
+    if item > 1 start
+        set total total + item
+    end
+end
+"#,
+    );
+    let arg = script.to_string_lossy().to_string();
+
+    let output = run_vexel(&workspace, &[&arg]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stderr_text(&output),
+        "line 6: Comparison operands must both be numbers, booleans, or strings\n"
+    );
+}
+
 #[test]
 fn reports_runtime_errors_for_invalid_bracket_property_access() {
     let workspace = create_workspace("runtime_error_bracket_access");
@@ -95,6 +162,32 @@ print m.hidden()
     assert!(stderr_text(&output).contains("Function 'm.hidden' is not exported"));
 }
 
+#[test]
+fn rejects_selectively_importing_unexported_functions() {
+    let workspace = create_workspace("private_selective_import");
+    write_workspace_file(
+        &workspace,
+        "module.vx",
+        r#"
+function hidden() start
+    return 99
+end
+"#,
+    );
+    let script = write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+import { hidden } from "./module.vx"
+"#,
+    );
+    let arg = script.to_string_lossy().to_string();
+
+    let output = run_vexel(&workspace, &[&arg]);
+    assert!(!output.status.success());
+    assert!(stderr_text(&output).contains("Function 'hidden' is not exported by './module.vx'"));
+}
+
 #[test]
 fn test_blocks_do_not_run_without_test_flag() {
     let workspace = create_workspace("tests_skipped");
@@ -145,3 +238,242 @@ end
         "Running test: sample\nfrom helper\nTest 'sample' finished\n"
     );
 }
+
+#[test]
+fn test_flag_fails_when_assertions_fail() {
+    let workspace = create_workspace("tests_assert_none");
+    let script = write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+test "passes" start
+    set _ assert_none(null)
+end
+
+test "fails" start
+    set _ assert_none(1)
+end
+"#,
+    );
+    let arg = script.to_string_lossy().to_string();
+
+    let output = run_vexel(&workspace, &["--test", &arg]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout_text(&output),
+        "Running test: passes\nTest 'passes' finished\nRunning test: fails\nAssertion failed: expected null, got 1\nTest 'fails' failed (1 assertion(s) failed)\nAssertions: 1 passed, 1 failed\n"
+    );
+    assert!(stderr_text(&output).contains("1 test(s) failed: fails"));
+}
+
+#[test]
+fn test_flag_checks_float_results_with_assert_near() {
+    let workspace = create_workspace("tests_assert_near");
+    let script = write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+test "sqrt" start
+    set _ assert_near(math_sqrt(2), 1.4142, 0.0001)
+end
+"#,
+    );
+    let arg = script.to_string_lossy().to_string();
+
+    let output = run_vexel(&workspace, &["--test", &arg]);
+    assert!(
+        output.status.success(),
+        "stderr was: {}",
+        stderr_text(&output)
+    );
+    assert_eq!(
+        stdout_text(&output),
+        "Running test: sqrt\nTest 'sqrt' finished\nAssertions: 1 passed, 0 failed\n"
+    );
+}
+
+#[test]
+fn assertions_are_summarized_when_the_script_ends() {
+    let workspace = create_workspace("assertion_summary");
+    let script = write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set total math_add(2, 3)
+set _ assert_equal(total, 5)
+set _ assert_equal(total, 6, "total should include tax")
+set _ assert_equal("a", "a", "labels match")
+set _ assert_none(null)
+set _ assert_equal(true, false)
+print "done"
+"#,
+    );
+    let arg = script.to_string_lossy().to_string();
+
+    let output = run_vexel(&workspace, &[&arg]);
+    assert!(
+        output.status.success(),
+        "stderr was: {}",
+        stderr_text(&output)
+    );
+    assert_eq!(
+        stdout_text(&output),
+        "Assertion failed: total should include tax (5 != 6)\nAssertion failed: true != false\ndone\nAssertions: 3 passed, 2 failed\n"
+    );
+}
+
+#[test]
+fn breakpoint_inspects_and_updates_the_paused_scope() {
+    let workspace = create_workspace("breakpoint");
+    let script = write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function scale(n) start
+    set factor 3
+    breakpoint()
+    return n * factor
+end
+
+print scale(2)
+"#,
+    );
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_vexel"))
+        .arg(&script)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn vexel binary");
+    child
+        .stdin
+        .as_mut()
+        .expect("missing child stdin")
+        .write_all(b"print n\nprint factor\nset factor 10\ncontinue\n")
+        .expect("failed to write breakpoint commands");
+    let output = child.wait_with_output().expect("failed to wait on child");
+
+    assert!(
+        output.status.success(),
+        "stderr was: {}",
+        stderr_text(&output)
+    );
+    assert_eq!(
+        stdout_text(&output),
+        "Breakpoint hit. Type 'continue' to resume.\n(breakpoint) 2\n(breakpoint) 3\n(breakpoint) (breakpoint) 20\n"
+    );
+}
+
+#[test]
+fn log_messages_below_configured_level_are_suppressed() {
+    let workspace = create_workspace("log_level");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set _ log("info", "visible by default")
+set _ set_log_level("error")
+set _ log("info", "hidden info")
+set _ log("error", "shown error")
+print "done"
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "stderr was: {}",
+        stderr_text(&output)
+    );
+    assert_eq!(stdout_text(&output), "done\n");
+    let stderr = stderr_text(&output);
+    assert!(stderr.contains("[INFO] visible by default"));
+    assert!(!stderr.contains("hidden info"));
+    assert!(stderr.contains("[ERROR] shown error"));
+}
+
+#[test]
+fn trace_flag_reports_statements_on_stderr() {
+    let workspace = create_workspace("trace_flag");
+    let script = write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set x 2
+function double(v) start
+    return v * 2
+end
+print double(x)
+"#,
+    );
+    let arg = script.to_string_lossy().to_string();
+
+    let output = run_vexel(&workspace, &["--trace", &arg]);
+    assert!(
+        output.status.success(),
+        "stderr was: {}",
+        stderr_text(&output)
+    );
+    assert_eq!(stdout_text(&output), "4\n");
+    let stderr = stderr_text(&output);
+    assert_eq!(
+        stderr,
+        "[trace] line 2: set x 2\n\
+         [trace] line 3: function double(v) start\n\
+         [trace] line 6: print double(x)\n\
+         [trace] line 4: return v * 2\n"
+    );
+}
+
+#[test]
+fn profile_flag_reports_sorted_function_call_counts() {
+    let workspace = create_workspace("profile_flag");
+    let script = write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function square(v) start
+    return v * v
+end
+
+set total 0
+for i in array_range(25) start
+    set total total + square(i)
+end
+print total
+"#,
+    );
+    let arg = script.to_string_lossy().to_string();
+
+    let output = run_vexel(&workspace, &["--profile", &arg]);
+    assert!(
+        output.status.success(),
+        "stderr was: {}",
+        stderr_text(&output)
+    );
+    assert_eq!(stdout_text(&output), "4900\n");
+    assert_eq!(
+        stderr_text(&output),
+        "Profile (function calls):\n  square: 25\n  array_range: 1\n"
+    );
+}
+
+#[test]
+fn process_exit_stops_the_script_with_the_given_status() {
+    let workspace = create_workspace("process_exit");
+    let script = write_workspace_file(
+        &workspace,
+        "main.vx",
+        "print \"before\"\nprocess_exit(3)\nprint \"after\"\n",
+    );
+    let arg = script.to_string_lossy().to_string();
+
+    let output = run_vexel(&workspace, &[&arg]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(stdout_text(&output), "before\n");
+
+    let script = write_workspace_file(&workspace, "clamped.vx", "process_exit(-4)\n");
+    let arg = script.to_string_lossy().to_string();
+    assert_eq!(run_vexel(&workspace, &[&arg]).status.code(), Some(0));
+}
@@ -250,6 +250,98 @@ print m.next()
     assert_stdout_lines(&output, &["6", "1", "2"]);
 }
 
+#[test]
+fn executes_module_functions_that_call_their_own_imports() {
+    let workspace = create_workspace("import_chain");
+    write_workspace_file(
+        &workspace,
+        "lib/base.vx",
+        r#"
+export function unit() start
+    return 10
+end
+"#,
+    );
+    write_workspace_file(
+        &workspace,
+        "lib/middle.vx",
+        r#"
+import base from "./base.vx"
+import { unit } from "./base.vx"
+
+export function twice() start
+    return base.unit() + unit()
+end
+"#,
+    );
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+import middle from "./lib/middle.vx"
+print middle.twice()
+print string_length("ok")
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["20", "2"]);
+}
+
+#[test]
+fn executes_selectively_imported_and_reexported_functions() {
+    let workspace = create_workspace("import_selective");
+    write_workspace_file(
+        &workspace,
+        "module.vx",
+        r#"
+set step 1
+
+function helper(x) start
+    return x + step
+end
+
+export function inc(x) start
+    return helper(x)
+end
+
+export function dec(x) start
+    return x - step
+end
+"#,
+    );
+    write_workspace_file(
+        &workspace,
+        "facade.vx",
+        r#"
+export { dec } from "./module.vx"
+"#,
+    );
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+import { inc } from "./module.vx"
+import f from "./facade.vx"
+print inc(5)
+print f.dec(5)
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["6", "4"]);
+}
+
 #[test]
 fn resolves_imports_relative_to_importing_script() {
     let workspace = create_workspace("import_relative");
@@ -280,6 +372,58 @@ print m.inc(9)
     assert_stdout_lines(&output, &["10"]);
 }
 
+#[test]
+fn resolves_nested_imports_relative_to_the_nested_module() {
+    let workspace = create_workspace("import_nested_relative");
+    write_workspace_file(
+        &workspace,
+        "app/lib/util/step.vx",
+        r#"
+export function size() start
+    return 5
+end
+"#,
+    );
+    write_workspace_file(
+        &workspace,
+        "app/lib/math.vx",
+        r#"
+import step from "./util/step.vx"
+
+export function add_step(x) start
+    return x + step.size()
+end
+"#,
+    );
+    // Resolving the nested import against the entry script's directory would
+    // pick up this file instead.
+    write_workspace_file(
+        &workspace,
+        "app/util/step.vx",
+        r#"
+export function size() start
+    return 100
+end
+"#,
+    );
+    write_workspace_file(
+        &workspace,
+        "app/main.vx",
+        r#"
+import m from "./lib/math.vx"
+print m.add_step(1)
+"#,
+    );
+
+    let output = run_script(&workspace, "app/main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["6"]);
+}
+
 #[test]
 fn executes_json_round_trip() {
     let workspace = create_workspace("json_roundtrip");
@@ -434,7 +578,7 @@ fn executes_try_catch() {
 try start
     print missing_value
 catch err start
-    print err
+    print err.message
 end
 print "after"
 "#,
@@ -476,3 +620,1613 @@ print -5 + 2
     );
     assert_stdout_lines(&output, &["7", "9", "1", "4", "8", "2", "5", "-2", "-3"]);
 }
+
+#[test]
+fn loads_shared_module_once_across_nested_imports() {
+    let workspace = create_workspace("import_cache");
+    write_workspace_file(
+        &workspace,
+        "shared.vx",
+        r#"
+print "loading shared"
+
+export function value() start
+    return 7
+end
+"#,
+    );
+    write_workspace_file(
+        &workspace,
+        "helper.vx",
+        r#"
+import s from "./shared.vx"
+
+export function doubled() start
+    return 14
+end
+"#,
+    );
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+import s from "./shared.vx"
+import h from "./helper.vx"
+import again from "./shared.vx"
+print s.value()
+print again.value()
+print h.doubled()
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["loading shared", "7", "7", "14"]);
+}
+
+#[test]
+fn cyclic_imports_do_not_recurse_forever() {
+    let workspace = create_workspace("import_cycle");
+    write_workspace_file(
+        &workspace,
+        "a.vx",
+        r#"
+import b from "./b.vx"
+
+export function name() start
+    return "a"
+end
+"#,
+    );
+    write_workspace_file(
+        &workspace,
+        "b.vx",
+        r#"
+import a from "./a.vx"
+
+export function name() start
+    return "b"
+end
+"#,
+    );
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+import a from "./a.vx"
+import b from "./b.vx"
+print a.name()
+print b.name()
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["a", "b"]);
+}
+
+#[test]
+fn recursive_calls_complete_without_copying_function_bodies() {
+    let workspace = create_workspace("recursive_calls");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function fib(n) start
+    if n < 2 start
+        return n
+    end
+    set a fib(n - 1)
+    set b fib(n - 2)
+    return a + b
+end
+function down(n) start
+    if n == 0 start
+        return "bottom"
+    end
+    return down(n - 1)
+end
+print fib(18)
+print down(300)
+"#,
+    );
+
+    let started = std::time::Instant::now();
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["2584", "bottom"]);
+    assert!(
+        started.elapsed() < std::time::Duration::from_secs(20),
+        "recursive calls took {:?}",
+        started.elapsed()
+    );
+}
+
+#[test]
+fn long_running_loops_complete_and_propagate_control_flow() {
+    let workspace = create_workspace("long_loops");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set total 0
+set i 0
+while i < 50000 start
+    set i i + 1
+    if i % 2 == 0 start
+        continue
+    end
+    set total total + 1
+end
+print total
+
+set last 0
+for n in array_range(20000) start
+    if n == 15000 start
+        break
+    end
+    set last n
+end
+print last
+
+function find_first_over(limit) start
+    for n in array_range(100000) start
+        if n > limit start
+            return n
+        end
+    end
+    return -1
+end
+print find_first_over(30000)
+"#,
+    );
+
+    let started = std::time::Instant::now();
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["25000", "14999", "30001"]);
+    assert!(
+        started.elapsed() < std::time::Duration::from_secs(20),
+        "loops took {:?}",
+        started.elapsed()
+    );
+}
+
+#[test]
+fn lookup_heavy_scripts_resolve_variables_functions_and_modules() {
+    let workspace = create_workspace("lookup_heavy");
+    write_workspace_file(
+        &workspace,
+        "util.vx",
+        r#"
+export function double(n) start
+    return n * 2
+end
+"#,
+    );
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+import util from "./util.vx"
+set a 1
+set b 2
+set c 3
+function mix(a, b) start
+    return a * 10 + b + c
+end
+set total 0
+for i in array_range(200) start
+    set total total + mix(a, b) + util.double(i)
+end
+print total
+print mix(b, a)
+print a
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["42800", "24", "1"]);
+}
+
+#[test]
+fn function_bodies_match_top_level_execution() {
+    let workspace = create_workspace("function_bodies");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function helper(v) start
+    return v * 3
+end
+
+function work(n) start
+    set total 0
+    set i 0
+    set meta {count: 0}
+    while i < n start
+        set i i + 1
+        if i % 5 == 0 start
+            continue
+        end
+        if i > 40000 start
+            break
+        end
+        set total math_add(total, helper(i % 7)) - -1
+        set meta.count meta.count + 1
+    end
+    for x in [1, 2, 3] start
+        set total total + x
+    end
+    try start
+        set total total + missing
+    catch err start
+        set total total + 1
+    end
+    return "${total} ${meta.count}"
+end
+
+set n 50000
+set total 0
+set i 0
+set meta {count: 0}
+while i < n start
+    set i i + 1
+    if i % 5 == 0 start
+        continue
+    end
+    if i > 40000 start
+        break
+    end
+    set total math_add(total, helper(i % 7)) - -1
+    set meta.count meta.count + 1
+end
+for x in [1, 2, 3] start
+    set total total + x
+end
+try start
+    set total total + missing
+catch err start
+    set total total + 1
+end
+print "${total} ${meta.count}"
+print work(n)
+"#,
+    );
+
+    let started = std::time::Instant::now();
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["319989 32000", "319989 32000"]);
+    assert!(
+        started.elapsed() < std::time::Duration::from_secs(20),
+        "tight loops took {:?}",
+        started.elapsed()
+    );
+}
+
+#[test]
+fn supports_float_constants_and_mixed_arithmetic() {
+    let workspace = create_workspace("float_constants");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set circumference 2 * math_pi()
+print circumference > 6.28
+print circumference < 6.29
+print math_e() > 2.718
+print 7 / 2
+print 7.0 / 2
+print 1.5 + 1.5 == 3
+print type_of(math_pi())
+print 1.5 + 1.5
+set whole 2.0
+print "${whole} and ${whole + 0.5}"
+print array_join([1.0, 2.5, 3], ",")
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(
+        &output,
+        &[
+            "true",
+            "true",
+            "true",
+            "3",
+            "3.5",
+            "true",
+            "number",
+            "3.0",
+            "2.0 and 2.5",
+            "1.0,2.5,3",
+        ],
+    );
+}
+
+#[test]
+fn array_count_counts_elements_matching_a_predicate() {
+    let workspace = create_workspace("array_count");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function above_two(n) start
+    return n > 2
+end
+
+print array_count([1, 2, 3, 4, 5], "above_two")
+print array_count([], "above_two")
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["3", "0"]);
+}
+
+#[test]
+fn dispatch_calls_the_function_named_by_a_table_entry() {
+    let workspace = create_workspace("dispatch");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function greet(name) start
+    return "hello ${name}"
+end
+
+function add(a, b) start
+    return a + b
+end
+
+set commands {greet: "greet", sum: "add"}
+print dispatch(commands, "greet", ["vexel"])
+print dispatch(commands, "sum", [2, 3])
+
+try start
+    print dispatch(commands, "missing", [])
+catch err start
+    print err.message
+end
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(
+        &output,
+        &["hello vexel", "5", "dispatch table has no entry 'missing'"],
+    );
+}
+
+#[test]
+fn array_flat_map_concatenates_callback_results() {
+    let workspace = create_workspace("array_flat_map");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function expand(n) start
+    return array_range(n)
+end
+
+function flatten_all(items) start
+    return array_flat_map(items, "expand")
+end
+
+set flat array_flat_map([1, 2, 3, 4], "expand")
+print array_length(flat)
+print array_join(flat, ",")
+print array_length(flatten_all([5, 0, 2]))
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["10", "0,0,1,0,1,2,0,1,2,3", "7"]);
+}
+
+#[test]
+fn array_group_by_buckets_elements_by_callback_key() {
+    let workspace = create_workspace("array_group_by");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function parity(n) start
+    if n % 2 == 0 start
+        return "even"
+    end
+    return "odd"
+end
+
+set groups array_group_by([1, 2, 3, 4, 5], "parity")
+print array_length(groups.even)
+print array_length(groups.odd)
+print array_join(groups.odd, ",")
+print array_join(object_keys(groups), ",")
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["2", "3", "1,3,5", "odd,even"]);
+}
+
+#[test]
+fn array_partition_splits_on_a_predicate() {
+    let workspace = create_workspace("array_partition");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function is_even(n) start
+    return n % 2 == 0
+end
+
+set parts array_partition([1, 2, 3, 4], "is_even")
+set evens array_get(parts, 0)
+set odds array_get(parts, 1)
+print array_length(parts)
+print array_length(evens)
+print array_length(odds)
+print array_join(evens, ",")
+print array_join(odds, ",")
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["2", "2", "2", "2,4", "1,3"]);
+}
+
+#[test]
+fn in_operator_checks_arrays_objects_and_strings() {
+    let workspace = create_workspace("in_operator");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set items ["a", "b", 3]
+set config {key: 1}
+
+if "a" in items start
+    print "array hit"
+end
+print 4 in items
+print 3.0 in items
+
+if "key" in config start
+    print "object hit"
+end
+print "missing" in config
+
+set index 0
+print "ell" in "hello"
+print "xyz" in "hello"
+
+function has_b(values) start
+    return "b" in values
+end
+print has_b(items)
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(
+        &output,
+        &[
+            "array hit",
+            "false",
+            "true",
+            "object hit",
+            "false",
+            "true",
+            "false",
+            "true",
+        ],
+    );
+}
+
+#[test]
+fn conditional_expressions_pick_the_matching_branch() {
+    let workspace = create_workspace("conditional_expression");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set x 5
+set y (x > 0 ? 1 : -1)
+print y
+set x -3
+set y (x > 0 ? 1 : -1)
+print y
+
+function sign(n) start
+    return n > 0 ? "positive" : n < 0 ? "negative" : "zero"
+end
+print sign(0)
+print sign(-8)
+print "a?b:c"
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["1", "-1", "zero", "negative", "a?b:c"]);
+}
+
+#[test]
+fn compound_assignment_updates_variables_in_place() {
+    let workspace = create_workspace("compound_assignment");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set total 0
+for i in array_range(5) start
+    set total += i
+end
+print total
+
+set total *= 3
+set total -= 2
+set total /= 4
+print total
+
+set counter {hits: 1}
+set counter.hits += 10
+print counter.hits
+
+set packed 1
+set packed +=4
+set packed *=2
+set packed -=3
+set packed /=7
+print packed
+"#,
+    );
+    write_workspace_file(&workspace, "undefined.vx", "set missing += 1\n");
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["10", "7", "11", "1"]);
+
+    let output = run_script(&workspace, "undefined.vx");
+    assert!(!output.status.success());
+    assert!(stderr_text(&output).contains("Undefined variable 'missing'"));
+}
+
+#[test]
+fn incr_and_decr_step_numeric_variables() {
+    let workspace = create_workspace("incr_decr");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set counter 0
+set remaining 10
+while counter < 5 start
+    incr counter
+    decr remaining
+end
+print counter
+print remaining
+"#,
+    );
+    write_workspace_file(&workspace, "text.vx", "set label \"x\"\nincr label\n");
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["5", "5"]);
+
+    let output = run_script(&workspace, "text.vx");
+    assert!(!output.status.success());
+    assert!(stderr_text(&output).contains("requires numeric operands"));
+}
+
+#[test]
+fn bare_call_statements_discard_their_result() {
+    let workspace = create_workspace("bare_call_statement");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function answer() start
+    return 42
+end
+
+function run() start
+    answer()
+    math_add(1, 2)
+    return "done"
+end
+
+answer()
+string_to_upper("quiet")
+print run()
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["done"]);
+}
+
+#[test]
+fn underscore_set_target_runs_expression_without_binding() {
+    let workspace = create_workspace("discard_target");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set _ write_file("out.txt", "written")
+print read_file("out.txt")
+
+function touch() start
+    set _ append_file("out.txt", "!")
+    return null
+end
+set _ touch()
+print read_file("out.txt")
+print _
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(!output.status.success());
+    assert_stdout_lines(&output, &["written", "written!"]);
+    assert!(stderr_text(&output).contains("Undefined variable '_'"));
+}
+
+#[test]
+fn undefined_variable_errors_are_catchable_and_abort_when_uncaught() {
+    let workspace = create_workspace("undefined_variable_error");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function read_missing() start
+    return missing_inside + 1
+end
+
+try start
+    print missing_value
+catch err start
+    print "caught: ${err.message}"
+end
+
+try start
+    print read_missing()
+catch err start
+    print "caught: ${err.message}"
+end
+
+print "still running"
+print never_defined
+print "unreachable"
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(!output.status.success());
+    assert_stdout_lines(
+        &output,
+        &[
+            "caught: Undefined variable 'missing_value'",
+            "caught: Undefined variable 'missing_inside'",
+            "still running",
+        ],
+    );
+    assert!(stderr_text(&output).contains("Undefined variable 'never_defined'"));
+}
+
+#[test]
+fn defined_reports_whether_a_variable_exists() {
+    let workspace = create_workspace("defined_builtin");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+print defined("x")
+set x 1
+print defined("x")
+
+function sees_outer() start
+    return defined("x")
+end
+print sees_outer()
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["false", "true", "true"]);
+}
+
+#[test]
+fn unset_removes_variables() {
+    let workspace = create_workspace("unset_statement");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set x 1
+print defined("x")
+unset x
+print defined("x")
+
+set total 10
+function reset_total() start
+    unset total
+end
+reset_total()
+print defined("total")
+
+try start
+    unset x
+catch err start
+    print err.message
+end
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(
+        &output,
+        &["true", "false", "false", "Undefined variable 'x'"],
+    );
+}
+
+#[test]
+fn repeat_runs_body_a_fixed_number_of_times() {
+    let workspace = create_workspace("repeat_loop");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+repeat 3 start
+    print "hello"
+end
+
+function count_to(limit) start
+    set n 0
+    repeat 10 start
+        incr n
+        if n == limit start
+            break
+        end
+    end
+    return n
+end
+print count_to(4)
+
+repeat 0 start
+    print "never"
+end
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["hello", "hello", "hello", "4"]);
+}
+
+#[test]
+fn for_from_to_iterates_inclusive_numeric_ranges() {
+    let workspace = create_workspace("range_loop");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set total 0
+for i from 1 to 5 start
+    set total += i
+end
+print total
+
+function evens(limit) start
+    set found []
+    for i from 0 to limit step 2 start
+        set found array_push(found, i)
+    end
+    return found
+end
+print evens(9)
+
+for i from 3 to 1 step -1 start
+    print i
+end
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["15", "[0, 2, 4, 6, 8]", "3", "2", "1"]);
+}
+
+#[test]
+fn frozen_objects_reject_property_assignment() {
+    let workspace = create_workspace("object_freeze");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set config object_freeze({name: "vexel", limits: {threads: 4}})
+set copy config
+
+try start
+    set copy.name "changed"
+catch err start
+    print err.type
+    print err.message
+end
+
+try start
+    set config.limits.threads 8
+catch err start
+    print err.message
+end
+
+print config.name
+print config.limits.threads
+print type_of(config)
+print object_keys(config)
+
+set thawed object_merge(config, {})
+set thawed.name "changed"
+print thawed.name
+
+print is_object(config)
+print object_size(config)
+print json_stringify(config)
+
+set listed array_first([config])
+try start
+    set listed.name "changed"
+catch err start
+    print err.message
+end
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(
+        &output,
+        &[
+            "type_error",
+            "Cannot assign a property of a frozen object",
+            "Cannot assign a property of a frozen object",
+            "vexel",
+            "4",
+            "object",
+            "[\"name\", \"limits\"]",
+            "changed",
+            "true",
+            "2",
+            r#"{"name":"vexel","limits":{"threads":4}}"#,
+            "Cannot assign a property of a frozen object",
+        ],
+    );
+}
+
+#[test]
+fn object_keys_keep_insertion_order() {
+    let workspace = create_workspace("object_key_order");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+print object_keys(json_parse(read_file("data.json")))
+set literal {zeta: 1, alpha: 2, mid: 3}
+print object_keys(literal)
+set created object_create("y", 1, "b", 2)
+print object_keys(object_merge(created, {a: 3, y: 4}))
+set literal.beta 4
+print literal
+print json_stringify(literal)
+"#,
+    );
+    write_workspace_file(&workspace, "data.json", r#"{"z":1,"a":2}"#);
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(
+        &output,
+        &[
+            r#"["z", "a"]"#,
+            r#"["zeta", "alpha", "mid"]"#,
+            r#"["y", "b", "a"]"#,
+            r#"{zeta: 1, alpha: 2, mid: 3, beta: 4}"#,
+            r#"{"zeta":1,"alpha":2,"mid":3,"beta":4}"#,
+        ],
+    );
+}
+
+#[test]
+fn random_seed_makes_random_functions_reproducible() {
+    let workspace = create_workspace("random_seed");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set items array_range(20)
+random_seed(42)
+print array_shuffle(items)
+print [random_choice(items), random_choice(items), random_choice(items)]
+random_seed(42)
+print array_shuffle(items)
+print [random_choice(items), random_choice(items), random_choice(items)]
+"#,
+    );
+
+    let first = run_script(&workspace, "main.vx");
+    assert!(
+        first.status.success(),
+        "script failed: {}",
+        stderr_text(&first)
+    );
+    let lines: Vec<String> = String::from_utf8_lossy(&first.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0..2], lines[2..4]);
+
+    let second = run_script(&workspace, "main.vx");
+    assert_eq!(first.stdout, second.stdout);
+}
+
+#[test]
+fn parallel_map_matches_sequential_results_in_order() {
+    let workspace = create_workspace("parallel_map");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set offset 7
+
+function crunch(n) start
+    set total 0
+    for i from 1 to 2000 start
+        set total (total + i * n) % 1000
+    end
+    return total + offset
+end
+
+set inputs array_range(12)
+set sequential []
+for n in inputs start
+    set sequential array_push(sequential, crunch(n))
+end
+print sequential
+print parallel_map(inputs, "crunch")
+print parallel_map([], "crunch")
+try start
+    print parallel_map([1], "missing")
+catch err start
+    print err.message
+end
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 4, "unexpected output: {}", stdout);
+    assert_eq!(lines[0], lines[1]);
+    assert_eq!(lines[2], "[]");
+    assert_eq!(lines[3], "Unknown function 'missing'");
+}
+
+#[test]
+fn spawned_threads_sleep_independently_and_join() {
+    let workspace = create_workspace("thread_sleep");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function nap(label, millis) start
+    thread_sleep_ms(millis)
+    return "${label} woke"
+end
+
+set first thread_spawn("nap", "first", 400)
+set second thread_spawn("nap", "second", 400)
+print "main keeps running"
+print thread_join(first)
+print thread_join(second)
+"#,
+    );
+
+    let started = std::time::Instant::now();
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(
+        &output,
+        &["main keeps running", "first woke", "second woke"],
+    );
+    // Back-to-back sleeps would take at least 800ms; the slack covers process
+    // start-up on a loaded machine.
+    assert!(
+        started.elapsed() < std::time::Duration::from_millis(800),
+        "threads did not sleep in parallel: {:?}",
+        started.elapsed()
+    );
+}
+
+#[test]
+fn atomic_counters_are_shared_across_spawned_threads() {
+    let workspace = create_workspace("atomic_counter");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function bump(counter, times) start
+    repeat times start
+        atomic_add(counter, 1)
+    end
+    return times
+end
+
+set counter atomic_new(0)
+set threads []
+for i from 1 to 6 start
+    set threads array_push(threads, thread_spawn("bump", counter, 250))
+end
+for id in threads start
+    thread_join(id)
+end
+print atomic_get(counter)
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["1500"]);
+}
+
+#[test]
+fn threads_spawned_in_a_tight_loop_can_all_be_joined() {
+    let workspace = create_workspace("thread_spawn_ids");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function square(n) start
+    return n * n
+end
+
+set ids []
+for i from 0 to 63 start
+    set ids array_push(ids, thread_spawn("square", i))
+end
+set total 0
+for id in ids start
+    set total total + thread_join(id)
+end
+print array_length(ids)
+print total
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["64", "85344"]);
+}
+
+#[test]
+fn workers_can_call_private_helpers_of_imported_functions() {
+    let workspace = create_workspace("worker_module_helpers");
+    write_workspace_file(
+        &workspace,
+        "util.vx",
+        r#"
+export function offset(n) start
+    return n + 1
+end
+"#,
+    );
+    write_workspace_file(
+        &workspace,
+        "lib.vx",
+        r#"
+import { offset } from "./util.vx"
+set factor 10
+
+function helper(n) start
+    return offset(n * factor)
+end
+
+export function scaled(n) start
+    return helper(n)
+end
+"#,
+    );
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+import { scaled } from "./lib.vx"
+print parallel_map([1, 2, 3], "scaled")
+print thread_join(thread_spawn("scaled", 4))
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["[11, 21, 31]", "41"]);
+}
+
+#[test]
+fn thread_join_returns_objects_and_arrays_intact() {
+    let workspace = create_workspace("thread_structured_results");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function describe(user, tags) start
+    set user.tags tags
+    set user.count array_length(tags)
+    return user
+end
+
+function pairs(items) start
+    set result []
+    for item in items start
+        set result array_push(result, {item: item, doubled: item * 2})
+    end
+    return result
+end
+
+set user {name: "ada", nested: {level: 2}}
+set described thread_join(thread_spawn("describe", user, ["a", "b"]))
+print described
+print described.nested.level
+print user
+print thread_join(thread_spawn("pairs", [1, 2]))
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(
+        &output,
+        &[
+            r#"{name: "ada", nested: {level: 2}, tags: ["a", "b"], count: 2}"#,
+            "2",
+            r#"{name: "ada", nested: {level: 2}}"#,
+            "[{item: 1, doubled: 2}, {item: 2, doubled: 4}]",
+        ],
+    );
+}
+
+#[test]
+fn thread_join_all_returns_results_in_order() {
+    let workspace = create_workspace("thread_join_all");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function slow_label(label, millis) start
+    thread_sleep_ms(millis)
+    return label
+end
+
+set ids [thread_spawn("slow_label", "a", 60), thread_spawn("slow_label", "b", 0), thread_spawn("slow_label", "c", 30)]
+print thread_join_all(ids)
+print thread_join_all([ids[0], "no-such-thread"])
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &[r#"["a", "b", "c"]"#, "[null, null]"]);
+}
+
+#[test]
+fn thread_join_with_timeout_returns_null_for_running_threads() {
+    let workspace = create_workspace("thread_join_timeout");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function slow() start
+    thread_sleep_ms(400)
+    return "done"
+end
+
+set id thread_spawn("slow")
+print thread_join(id, 30)
+print thread_status(id)
+print thread_join(id, 5000)
+print thread_status(id)
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(&output, &["null", "running", "done", "null"]);
+}
+
+#[test]
+fn memoized_functions_run_once_per_argument() {
+    let workspace = create_workspace("memoize");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function expensive(n) start
+    print "computing ${n}"
+    return n * 10
+end
+
+function fib(n) start
+    if n < 2 start
+        return n
+    end
+    return fib(n - 1) + fib(n - 2)
+end
+
+memoize("expensive")
+print expensive(2)
+print expensive(2)
+print expensive(3)
+print expensive(2)
+
+memoize("fib")
+print fib(40)
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(
+        &output,
+        &[
+            "computing 2",
+            "20",
+            "20",
+            "computing 3",
+            "30",
+            "20",
+            "102334155",
+        ],
+    );
+}
+
+#[test]
+fn memoized_functions_keep_arguments_of_different_types_apart() {
+    let workspace = create_workspace("memoize_types");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function half(n) start
+    return n / 2
+end
+
+function kind(value) start
+    return type_of(value)
+end
+
+memoize("half")
+print half(3)
+print half(3.0)
+
+memoize("kind")
+print kind(3)
+print kind("3")
+print kind(true)
+print kind("true")
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(
+        &output,
+        &["1", "1.5", "number", "string", "boolean", "string"],
+    );
+}
+
+#[test]
+fn benchmark_blocks_run_their_body_and_report_timing() {
+    let workspace = create_workspace("benchmark_block");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+set total 0
+benchmark "sum loop" start
+    for i from 1 to 1000 start
+        set total total + i
+    end
+end
+print total
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "unexpected output: {}", stdout);
+    assert!(
+        lines[0].starts_with("Benchmark 'sum loop' took ") && lines[0].ends_with(" ms"),
+        "unexpected timing line: {}",
+        lines[0]
+    );
+    assert_eq!(lines[1], "500500");
+}
+
+#[test]
+fn caught_errors_are_objects_with_type_and_message() {
+    let workspace = create_workspace("structured_errors");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+function pair(a, b) start
+    return [a, b]
+end
+
+function describe(err) start
+    return "${err.type}: ${err.message}"
+end
+
+try start
+    print missing_value
+catch err start
+    print describe(err)
+end
+
+try start
+    print 1 + "one"
+catch err start
+    print describe(err)
+end
+
+try start
+    print pair(1)
+catch err start
+    print describe(err)
+end
+
+try start
+    print no_such_function()
+catch err start
+    print describe(err)
+end
+
+try start
+    print array_get(1, 2)
+catch err start
+    print err.type == "native_error"
+end
+
+try start
+    import helpers from "missing.vx"
+catch err start
+    print err.type
+end
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(
+        &output,
+        &[
+            "undefined_variable: Undefined variable 'missing_value'",
+            "type_error: Binary operator '+' requires numeric operands",
+            "arity_error: Function 'pair' expected 2 arguments but received 1",
+            "undefined_function: Unknown function 'no_such_function'",
+            "true",
+            "io_error",
+        ],
+    );
+}
+
+#[test]
+fn native_failures_are_catchable_and_report_a_reason() {
+    let workspace = create_workspace("native_failure_reasons");
+    write_workspace_file(
+        &workspace,
+        "main.vx",
+        r#"
+try start
+    print math_divide(1, 0)
+catch err start
+    print err.type
+    print err.message
+end
+
+try start
+    print number_from_string("abc")
+catch err start
+    print err.message
+end
+
+try start
+    print array_get(1, 2)
+catch err start
+    print err.message
+end
+
+try start
+    write_file("missing/out.txt", "x")
+catch err start
+    print string_starts_with(err.message, "Native function 'write_file' failed: cannot write 'missing/out.txt': ")
+end
+
+print "still running"
+"#,
+    );
+
+    let output = run_script(&workspace, "main.vx");
+    assert!(
+        output.status.success(),
+        "script failed: {}",
+        stderr_text(&output)
+    );
+    assert_stdout_lines(
+        &output,
+        &[
+            "native_error",
+            "Native function 'math_divide' failed: division by zero",
+            "Native function 'number_from_string' failed: 'abc' is not a valid integer",
+            "Native function 'array_get' failed for provided arguments",
+            "true",
+            "still running",
+        ],
+    );
+}
@@ -56,3 +56,95 @@ fn repl_handles_nested_blocks_before_execution() {
         "expected nested REPL script to run, stdout was: {stdout}"
     );
 }
+
+fn run_repl(input: &[u8]) -> std::process::Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_vexel"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn vexel binary");
+
+    child
+        .stdin
+        .as_mut()
+        .expect("missing child stdin")
+        .write_all(input)
+        .expect("failed to write REPL input");
+
+    child.wait_with_output().expect("failed to wait on child")
+}
+
+#[test]
+fn repl_keeps_functions_with_inner_blocks_callable_from_later_input() {
+    let output = run_repl(
+        b"function classify(n) start\nif n > 1 start\nreturn \"many\"\nend\nreturn \"one\"\nend\nprint classify(3)\nprint classify(1)\nexit\n",
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr was: {stderr}");
+    assert!(
+        stderr.is_empty(),
+        "expected no errors, stderr was: {stderr}"
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("many\n") && stdout.contains("one\n"),
+        "expected function to stay callable, stdout was: {stdout}"
+    );
+}
+
+#[test]
+fn repl_runs_loop_with_inner_if_only_after_final_end() {
+    let output = run_repl(
+        b"for x in [1, 2, 3] start\nif x == 2 start\nprint \"found\"\nend\nprint x\nend\nexit\n",
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr was: {stderr}");
+    assert!(
+        stderr.is_empty(),
+        "expected no errors, stderr was: {stderr}"
+    );
+
+    // One continuation prompt per buffered line proves nothing ran early.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(">> ... ... ... ... ... 1\nfound\n2\n3\n"),
+        "expected loop to run once after the final end, stdout was: {stdout}"
+    );
+}
+
+#[test]
+fn repl_reset_discards_defined_variables() {
+    let output = run_repl(b"set answer 42\nprint answer\n.reset\nprint answer\nexit\n");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("42\n"),
+        "expected value before reset, stdout was: {stdout}"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Undefined variable 'answer'"),
+        "expected variable to be gone after reset, stderr was: {stderr}"
+    );
+}
+
+#[test]
+fn repl_paste_mode_runs_buffered_program_at_terminator() {
+    let output = run_repl(
+        b".paste\nfunction double(n) start\n    return n * 2\nend\nset total double(4)\nprint total\n.end\nprint double(1)\nexit\n",
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr was: {stderr}");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("... ... ... ... ... ... 8\n>> 2\n"),
+        "expected pasted program to run once at .end, stdout was: {stdout}"
+    );
+}